@@ -0,0 +1,201 @@
+/*-------------------------------------
+  Sets of vertices
+
+    The search keeps the visited vertices, the rim and the edges of every vertice as sets of vertices.
+    On boards of up to 128 vertices a set is a single u128, and every operation is a machine instruction
+    or two. Larger boards get a WideSet: as many u64 words as the board needs, allocated at run time.
+    The search is written once for both (see VertexSet) and picks the set when the board is initialized.
+-------------------------------------*/
+
+/// A set of vertices, with room for the vertices of one board
+pub(crate) trait VertexSet: Clone + PartialEq {
+    /// The most vertices a set can hold
+    const CAPACITY: usize;
+
+    /// The empty set, with room for vertices 0..size
+    fn empty(size: usize) -> Self;
+
+    /// All the vertices 0..size
+    fn full(size: usize) -> Self;
+
+    fn contains(&self, k: usize) -> bool;
+
+    fn insert(&mut self, k: usize);
+
+    fn remove(&mut self, k: usize);
+
+    fn is_empty(&self) -> bool;
+
+    /// The number of vertices in the set
+    fn len(&self) -> u32;
+
+    /// The lowest vertice in the set, if any
+    fn first(&self) -> Option<usize>;
+
+    /// The vertices in both sets
+    fn and(&self, other: &Self) -> Self;
+
+    /// The vertices in this set but not in the other one
+    fn and_not(&self, other: &Self) -> Self;
+
+    /// The set and the neighbours of its vertices on a board n vertices wide, as far as they are `within` -
+    /// one step of a flood fill (`left` and `right` are the left and the right column, so the rows do not wrap around)
+    fn grow(&self, n: usize, left: &Self, right: &Self, within: &Self) -> Self;
+}
+
+impl VertexSet for u128 {
+    const CAPACITY: usize = 128;
+
+    #[inline]
+    fn empty(_size: usize) -> Self {
+        0
+    }
+
+    #[inline]
+    fn full(size: usize) -> Self {
+        if size == 128 {
+            !0
+        } else {
+            (1 << size) - 1
+        }
+    }
+
+    #[inline]
+    fn contains(&self, k: usize) -> bool {
+        *self >> k & 1 == 1
+    }
+
+    #[inline]
+    fn insert(&mut self, k: usize) {
+        *self |= 1 << k;
+    }
+
+    #[inline]
+    fn remove(&mut self, k: usize) {
+        *self &= !(1 << k);
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        *self == 0
+    }
+
+    #[inline]
+    fn len(&self) -> u32 {
+        self.count_ones()
+    }
+
+    #[inline]
+    fn first(&self) -> Option<usize> {
+        if *self == 0 {
+            None
+        } else {
+            Some(self.trailing_zeros() as usize)
+        }
+    }
+
+    #[inline]
+    fn and(&self, other: &Self) -> Self {
+        self & other
+    }
+
+    #[inline]
+    fn and_not(&self, other: &Self) -> Self {
+        self & !other
+    }
+
+    #[inline]
+    fn grow(&self, n: usize, left: &Self, right: &Self, within: &Self) -> Self {
+        (self | (self & !right) << 1 | (self & !left) >> 1 | self << n | self >> n) & within
+    }
+}
+
+/// A set of vertices on a board of any size, in 64 bit words
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct WideSet {
+    words: Vec<u64>,
+}
+
+impl WideSet {
+    fn map(&self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        WideSet {
+            words: self.words.iter().zip(&other.words).map(|(&a, &b)| f(a, b)).collect(),
+        }
+    }
+}
+
+impl VertexSet for WideSet {
+    const CAPACITY: usize = usize::MAX;
+
+    fn empty(size: usize) -> Self {
+        WideSet {
+            words: vec![0; size.div_ceil(64)],
+        }
+    }
+
+    fn full(size: usize) -> Self {
+        let mut set = WideSet::empty(size);
+        for (k, word) in set.words.iter_mut().enumerate() {
+            let bits = (size - 64 * k).min(64);
+            *word = if bits == 64 { !0 } else { (1 << bits) - 1 };
+        }
+        set
+    }
+
+    fn contains(&self, k: usize) -> bool {
+        self.words[k / 64] >> (k % 64) & 1 == 1
+    }
+
+    fn insert(&mut self, k: usize) {
+        self.words[k / 64] |= 1 << (k % 64);
+    }
+
+    fn remove(&mut self, k: usize) {
+        self.words[k / 64] &= !(1 << (k % 64));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    fn len(&self) -> u32 {
+        self.words.iter().map(|word| word.count_ones()).sum()
+    }
+
+    fn first(&self) -> Option<usize> {
+        let k = self.words.iter().position(|&word| word != 0)?;
+        Some(64 * k + self.words[k].trailing_zeros() as usize)
+    }
+
+    fn and(&self, other: &Self) -> Self {
+        self.map(other, |a, b| a & b)
+    }
+
+    fn and_not(&self, other: &Self) -> Self {
+        self.map(other, |a, b| a & !b)
+    }
+
+    fn grow(&self, n: usize, left: &Self, right: &Self, within: &Self) -> Self {
+        // word k of the set shifted up (or down) by `by` bits, with the vertices in `mask` left out before the shift
+        let word = |k: usize, mask: Option<&WideSet>| {
+            let word = self.words.get(k).copied().unwrap_or(0);
+            word & !mask.map_or(0, |mask| mask.words[k])
+        };
+        let up = |k: usize, by: usize, mask: Option<&WideSet>| {
+            let (skip, bits) = (by / 64, by % 64);
+            let high = if k >= skip { word(k - skip, mask) } else { 0 };
+            let low = if k > skip && bits > 0 { word(k - skip - 1, mask) >> (64 - bits) } else { 0 };
+            high << bits | low
+        };
+        let down = |k: usize, by: usize, mask: Option<&WideSet>| {
+            let (skip, bits) = (by / 64, by % 64);
+            let low = if k + skip < self.words.len() { word(k + skip, mask) } else { 0 };
+            let high = if k + skip + 1 < self.words.len() && bits > 0 { word(k + skip + 1, mask) << (64 - bits) } else { 0 };
+            low >> bits | high
+        };
+        let words = (0..self.words.len())
+            .map(|k| (self.words[k] | up(k, 1, Some(right)) | down(k, 1, Some(left)) | up(k, n, None) | down(k, n, None)) & within.words[k])
+            .collect();
+        WideSet { words }
+    }
+}
@@ -0,0 +1,220 @@
+/*-------------------------------------
+  Checkpoints
+
+    A long search is split into positions (see collect_positions), and a checkpoint holds everything needed
+    to continue it: the board size, the metrics added up so far, and the positions not yet searched - each
+    with its solution_path and the return edges opened along that path.
+    The positions come from a search that counts the rotated and mirrored copies of a loop from one of them
+    (see Board::set_folding).
+
+    The file is plain text:
+        roundtrip checkpoint
+        n 8
+        m 8
+        split_depth 16
+        elapsed_ms 73400
+        check_counter 31554093
+        ...
+        position 19 path 0,1,2,10,18,26 return 9>1
+-------------------------------------*/
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::error::Error;
+use crate::lattice::Lattice;
+use crate::metrics::Metrics;
+use crate::parallel::check_positions;
+use crate::search::{collect_positions, SearchPosition};
+use crate::Count;
+
+const HEADER: &str = "roundtrip checkpoint";
+
+/// The state of an unfinished search
+pub struct Checkpoint {
+    pub lattice: Lattice,
+    pub split_depth: usize,
+    pub metrics: Metrics,
+    /// the positions that remain to be searched
+    pub positions: Vec<SearchPosition>,
+}
+
+impl Checkpoint {
+    /// Write the checkpoint to a file (through a temporary file, so an interrupted write leaves the old checkpoint intact)
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let metrics = &self.metrics;
+        let elapsed = metrics.run_duration.elapsed().unwrap_or_default();
+        let mut text = format!("{}\n", HEADER);
+        text += &format!("n {}\nm {}\nsplit_depth {}\n", self.lattice.n(), self.lattice.m(), self.split_depth);
+        text += &format!("elapsed_ms {}\n", elapsed.as_millis());
+        text += &format!("check_counter {}\n", metrics.check_counter);
+        text += &format!("fail_counter_1 {}\n", metrics.fail_counter_1);
+        text += &format!("fail_counter_2 {}\n", metrics.fail_counter_2);
+        text += &format!("fail_counter_3 {}\n", metrics.fail_counter_3);
+        text += &format!("exception_counter {}\n", metrics.exception_counter);
+        text += &format!("solutions_counter {}\n", metrics.solutions_counter);
+        for position in &self.positions {
+            let solution_path: Vec<String> = position.solution_path.iter().map(|v| v.to_string()).collect();
+            let return_edges: Vec<String> = position.return_edges.iter().map(|(j, r)| format!("{}>{}", j, r)).collect();
+            text += &format!(
+                "position {} path {} return {}\n",
+                position.next_vertice,
+                solution_path.join(","),
+                return_edges.join(",")
+            );
+        }
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, text).map_err(|e| Error::io(&temporary, e))?;
+        fs::rename(&temporary, path).map_err(|e| Error::io(path, e))
+    }
+
+    /// Read a checkpoint written by `save`
+    pub fn load(path: &Path) -> Result<Checkpoint, Error> {
+        let text = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        let mut lines = text.lines().enumerate().map(|(k, line)| (k + 1, line));
+        match lines.next() {
+            Some((_, HEADER)) => {}
+            _ => return Err(invalid(1, "not a roundtrip checkpoint")),
+        }
+        let (mut n, mut m, mut split_depth) = (0, 0, 0);
+        let mut metrics = Metrics::new();
+        let mut positions = vec![];
+        for (line_number, line) in lines {
+            let mut words = line.split_whitespace();
+            let key = match words.next() {
+                Some(key) => key,
+                None => continue,
+            };
+            let value = words.next().ok_or_else(|| invalid(line_number, "missing value"))?;
+            match key {
+                "n" => n = number(line_number, value)?,
+                "m" => m = number(line_number, value)?,
+                "split_depth" => split_depth = number(line_number, value)?,
+                "elapsed_ms" => {
+                    let elapsed = Duration::from_millis(number(line_number, value)?);
+                    metrics.run_duration = SystemTime::now().checked_sub(elapsed).unwrap_or_else(SystemTime::now);
+                }
+                "check_counter" => metrics.check_counter = number(line_number, value)?,
+                "fail_counter_1" => metrics.fail_counter_1 = number(line_number, value)?,
+                "fail_counter_2" => metrics.fail_counter_2 = number(line_number, value)?,
+                "fail_counter_3" => metrics.fail_counter_3 = number(line_number, value)?,
+                "exception_counter" => metrics.exception_counter = number(line_number, value)?,
+                "solutions_counter" => metrics.solutions_counter = number(line_number, value)?,
+                "position" => {
+                    let next_vertice = number(line_number, value)?;
+                    let (mut solution_path, mut return_edges) = (vec![], vec![]);
+                    while let Some(list) = words.next() {
+                        let items = words.next().unwrap_or("");
+                        let items = items.split(',').filter(|item| !item.is_empty());
+                        match list {
+                            "path" => {
+                                for v in items {
+                                    solution_path.push(number(line_number, v)?);
+                                }
+                            }
+                            "return" => {
+                                for edge in items {
+                                    let mut ends = edge.split('>');
+                                    let j = number(line_number, ends.next().unwrap_or(""))?;
+                                    let r = number(line_number, ends.next().unwrap_or(""))?;
+                                    return_edges.push((j, r));
+                                }
+                            }
+                            other => return Err(invalid(line_number, &format!("unknown list {:?}", other))),
+                        }
+                    }
+                    let size = n * m;
+                    let outside = |v: &usize| *v >= size;
+                    if outside(&next_vertice)
+                        || solution_path.iter().any(outside)
+                        || return_edges.iter().any(|(j, r)| outside(j) || outside(r))
+                    {
+                        return Err(invalid(line_number, "position outside the board"));
+                    }
+                    positions.push(SearchPosition {
+                        solution_path,
+                        return_edges,
+                        next_vertice,
+                    });
+                }
+                other => return Err(invalid(line_number, &format!("unknown key {:?}", other))),
+            }
+        }
+        Ok(Checkpoint {
+            lattice: Lattice::new(n, m)?,
+            split_depth,
+            metrics,
+            positions,
+        })
+    }
+}
+
+fn invalid(line: usize, message: &str) -> Error {
+    Error::InvalidCheckpoint {
+        line,
+        message: message.to_string(),
+    }
+}
+
+fn number<T: std::str::FromStr>(line: usize, value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| invalid(line, &format!("invalid number {:?}", value)))
+}
+
+/// Count all solutions of a lattice, writing a checkpoint to `path` at least every `interval`.
+///
+/// The last checkpoint has no positions left, so resuming from it just reports the result.
+pub fn count_with_checkpoints(
+    lattice: &Lattice,
+    metrics: &mut Metrics,
+    threads: usize,
+    split_depth: usize,
+    path: &Path,
+    interval: Duration,
+) -> Result<Count, Error> {
+    let positions = collect_positions(lattice, split_depth, metrics)?;
+    let checkpoint = Checkpoint {
+        lattice: *lattice,
+        split_depth,
+        metrics: metrics.clone(),
+        positions,
+    };
+    checkpoint.save(path)?;
+    continue_checkpoint(checkpoint, metrics, threads, path, interval)
+}
+
+/// Continue the search saved in the checkpoint file at `path`, updating the file as the search goes on.
+///
+/// The metrics continue from the ones saved in the checkpoint.
+pub fn resume_from_checkpoint(path: &Path, metrics: &mut Metrics, threads: usize, interval: Duration) -> Result<(Lattice, Count), Error> {
+    let checkpoint = Checkpoint::load(path)?;
+    let lattice = checkpoint.lattice;
+    *metrics = checkpoint.metrics.clone();
+    let count = continue_checkpoint(checkpoint, metrics, threads, path, interval)?;
+    Ok((lattice, count))
+}
+
+fn continue_checkpoint(
+    mut checkpoint: Checkpoint,
+    metrics: &mut Metrics,
+    threads: usize,
+    path: &Path,
+    interval: Duration,
+) -> Result<Count, Error> {
+    let positions = std::mem::take(&mut checkpoint.positions);
+    let mut remaining: BTreeSet<usize> = (0..positions.len()).collect();
+    let mut last_save = SystemTime::now();
+    let lattice = checkpoint.lattice;
+    check_positions(&lattice, &positions, threads, metrics, |k, metrics| {
+        remaining.remove(&k);
+        if last_save.elapsed().unwrap_or_default() >= interval || remaining.is_empty() {
+            checkpoint.metrics = metrics.clone();
+            checkpoint.positions = remaining.iter().map(|&k| positions[k].clone()).collect();
+            checkpoint.save(path)?;
+            last_save = SystemTime::now();
+        }
+        Ok(())
+    })?;
+    Ok(Count::from(metrics.solutions_counter))
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::lattice::Lattice;
+use crate::Count;
+
+/// A closed loop through all vertices of a lattice.
+///
+/// The vertices are listed in the order the loop visits them, starting in vertex 0;
+/// the edge from the last vertex back to vertex 0 closes the loop.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub vertices: Vec<usize>,
+}
+
+impl Cycle {
+    pub fn new(vertices: Vec<usize>) -> Self {
+        Cycle { vertices }
+    }
+
+    /// The edges of the loop as (from, to) pairs, including the closing edge back to the first vertex
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let next = self.vertices.iter().skip(1).chain(self.vertices.first());
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// Is this a closed loop through all vertices of the lattice: every vertex has two edges, the edges all
+    /// join neighbours in the lattice, and (since the vertices are listed along one loop) there is a single component?
+    pub fn is_valid_on(&self, lattice: &Lattice) -> bool {
+        let size = lattice.size();
+        let mut degree = vec![0; size];
+        for (a, b) in self.edges() {
+            if !lattice.are_neighbours(a, b) {
+                return false;
+            }
+            degree[a] += 1;
+            degree[b] += 1;
+        }
+        // with two edges at every vertex, a list of n * m vertices holds each vertex exactly once
+        self.vertices.len() == size && degree.iter().all(|&d| d == 2)
+    }
+
+    /// Does the loop use the edge between a and b (in either direction)?
+    pub fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.edges().any(|(from, to)| (from, to) == (a, b) || (from, to) == (b, a))
+    }
+}
+
+/// The number of solutions of a board or puzzle, with the first ones found
+#[derive(Debug, Clone)]
+pub struct Solutions {
+    pub count: Count,
+    /// the first solutions found, up to the number asked for
+    pub cycles: Vec<Cycle>,
+}
+
+impl fmt::Display for Cycle {
+    /// The vertices separated by spaces, closed back at the first vertex: `0 1 5 4 0`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for v in &self.vertices {
+            write!(f, "{} ", v)?;
+        }
+        match self.vertices.first() {
+            Some(first) => write!(f, "{}", first),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Reasons a board can not be searched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// n > m (an n x m board has the same solutions as an m x n board)
+    NotHighAndThin { n: usize, m: usize },
+    /// n * m is less than 12
+    TooSmall { n: usize, m: usize },
+    /// n * m is odd, so there can be no closed loop through all vertices
+    OddSize { n: usize, m: usize },
+    /// the quarter turns and the diagonal mirrors are only symmetries of square boards
+    NotSquare { n: usize, m: usize },
+    /// n is too large for the frontier of the transfer matrix
+    TooWide { n: usize, max: usize },
+    /// the number of solutions does not fit in a u128 (build with the `bigint` feature)
+    CountOverflow,
+    /// a given edge does not connect two neighbour vertices
+    NotAnEdge { a: usize, b: usize },
+    /// a file could not be read or written
+    Io { path: String, message: String },
+    /// a puzzle file could not be understood (line and column count from 1)
+    Parse { line: usize, column: usize, message: String },
+    /// a checkpoint file could not be understood
+    InvalidCheckpoint { line: usize, message: String },
+}
+
+impl Error {
+    pub(crate) fn io(path: &Path, error: io::Error) -> Self {
+        Error::Io {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotHighAndThin { n, m } => {
+                write!(f, "n ({}) should be less or equal to m ({})", n, m)
+            }
+            Error::TooSmall { .. } => {
+                write!(f, "Too small! Board size n*m must be min 12.")
+            }
+            Error::OddSize { .. } => {
+                write!(f, "Invalid matrix size: n * m MUST be an even number")
+            }
+            Error::NotSquare { n, m } => {
+                write!(f, "The quarter turns and diagonal mirrors are only symmetries of square boards, not of {} x {}", n, m)
+            }
+            Error::TooWide { n, max } => {
+                write!(f, "n ({}) must be less or equal to {} for the transfer matrix", n, max)
+            }
+            Error::CountOverflow => {
+                write!(f, "The number of solutions is too large for a u128 - build with --features bigint")
+            }
+            Error::NotAnEdge { a, b } => {
+                write!(f, "There is no edge between vertices {} and {}", a, b)
+            }
+            Error::Io { path, message } => write!(f, "{}: {}", path, message),
+            Error::Parse { line, column, message } => {
+                write!(f, "line {}, column {}: {}", line, column, message)
+            }
+            Error::InvalidCheckpoint { line, message } => {
+                write!(f, "Invalid checkpoint (line {}): {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,154 @@
+/*-------------------------------------
+  Text format for puzzles and solutions
+
+    A board is drawn as it looks on paper, with a '.' for every dot. Between two dots there is
+        '-' or '|' - an edge that has been drawn
+        'x'        - an edge that can not be used
+        ' '        - an edge that is still open
+    so a 4 x 3 puzzle with two edges drawn and one forbidden looks like this:
+        .-. . .
+        |     x
+        . . . .
+
+        . . . .
+    Trailing spaces may be left out, and lines starting with '#' are comments.
+    Solutions are written in the same format, with the edges of the loop drawn and the
+    forbidden edges of the puzzle kept, so a solution can be diffed against its puzzle.
+-------------------------------------*/
+
+use crate::cycle::Cycle;
+use crate::error::Error;
+use crate::puzzle::Puzzle;
+
+fn parse_error(line: usize, column: usize, message: &str) -> Error {
+    Error::Parse {
+        line,
+        column,
+        message: message.to_string(),
+    }
+}
+
+/// Read a puzzle written in the text format
+pub fn parse_puzzle(text: &str) -> Result<Puzzle, Error> {
+    let mut lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(k, line)| (k + 1, line.trim_end()))
+        .filter(|(_, line)| !line.starts_with('#'))
+        .skip_while(|(_, line)| line.is_empty())
+        .collect();
+    while lines.last().is_some_and(|(_, line)| line.is_empty()) {
+        lines.pop();
+    }
+    let (first_line, first_row) = match lines.first() {
+        Some(&first) => first,
+        None => return Err(parse_error(1, 1, "no board found")),
+    };
+    let width = first_row.chars().count();
+    if width.is_multiple_of(2) {
+        return Err(parse_error(first_line, width, "a row must start and end with a dot"));
+    }
+    if lines.len().is_multiple_of(2) {
+        let (last_line, _) = lines[lines.len() - 1];
+        return Err(parse_error(last_line + 1, 1, "expected a row of dots"));
+    }
+    let (n, m) = (width.div_ceil(2), lines.len().div_ceil(2));
+    let (mut given, mut forbidden) = (vec![], vec![]);
+    for (row, &(line_number, line)) in lines.iter().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.len() > width {
+            return Err(parse_error(line_number, width + 1, "line is longer than the first row"));
+        }
+        for c in 0..width {
+            let ch = chars.get(c).copied().unwrap_or(' ');
+            let column = c + 1;
+            let v = (row / 2) * n + c / 2;
+            let (edge, drawn) = match (row % 2, c % 2) {
+                (0, 0) => {
+                    if ch != '.' {
+                        return Err(parse_error(line_number, column, &format!("expected '.', found {:?}", ch)));
+                    }
+                    continue;
+                }
+                (0, _) => ((v, v + 1), '-'),
+                (_, 0) => ((v, v + n), '|'),
+                _ => {
+                    if ch != ' ' {
+                        return Err(parse_error(line_number, column, &format!("expected ' ' inside a tile, found {:?}", ch)));
+                    }
+                    continue;
+                }
+            };
+            match ch {
+                ' ' => {}
+                'x' => forbidden.push(edge),
+                _ if ch == drawn => given.push(edge),
+                _ => {
+                    let message = format!("expected '{}', 'x' or ' ', found {:?}", drawn, ch);
+                    return Err(parse_error(line_number, column, &message));
+                }
+            }
+        }
+    }
+
+    let mut puzzle = Puzzle::new(n, m)?;
+    for (a, b) in given {
+        puzzle.add_given_edge(a, b)?;
+    }
+    for (a, b) in forbidden {
+        puzzle.add_forbidden_edge(a, b)?;
+    }
+    Ok(puzzle)
+}
+
+/// Write a puzzle in the text format
+pub fn write_puzzle(puzzle: &Puzzle) -> String {
+    let has = |edges: &[(usize, usize)], a: usize, b: usize| edges.contains(&(a, b)) || edges.contains(&(b, a));
+    write_grid(puzzle, |a, b| has(puzzle.given_edges(), a, b))
+}
+
+/// Write a completion of a puzzle in the text format
+pub fn write_solution(puzzle: &Puzzle, cycle: &Cycle) -> String {
+    write_grid(puzzle, |a, b| cycle.has_edge(a, b))
+}
+
+fn write_grid<F: Fn(usize, usize) -> bool>(puzzle: &Puzzle, drawn: F) -> String {
+    let (n, m) = (puzzle.n(), puzzle.m());
+    let forbidden = |a: usize, b: usize| {
+        let edges = puzzle.forbidden_edges();
+        edges.contains(&(a, b)) || edges.contains(&(b, a))
+    };
+    let symbol = |a: usize, b: usize, line: char| {
+        if drawn(a, b) {
+            line
+        } else if forbidden(a, b) {
+            'x'
+        } else {
+            ' '
+        }
+    };
+    let mut text = String::new();
+    for j in 0..m {
+        let mut dots = String::new();
+        for i in 0..n {
+            dots.push('.');
+            if i + 1 < n {
+                dots.push(symbol(j * n + i, j * n + i + 1, '-'));
+            }
+        }
+        text += dots.trim_end();
+        text.push('\n');
+        if j + 1 < m {
+            let mut edges = String::new();
+            for i in 0..n {
+                edges.push(symbol(j * n + i, (j + 1) * n + i, '|'));
+                if i + 1 < n {
+                    edges.push(' ');
+                }
+            }
+            text += edges.trim_end();
+            text.push('\n');
+        }
+    }
+    text
+}
@@ -0,0 +1,130 @@
+/*-------------------------------------
+  Cycles one at a time
+
+    The same search as check_board, but with the recursion turned into an explicit stack, so it can stop
+    after every solution and pick up where it left off when the next one is asked for.
+    Each frame on the stack belongs to a vertice on the solution_path and holds the neighbours not yet tried
+    from there, and how many return edges were open when the path arrived (the ones opened later are closed
+    again when the path leaves the vertice).
+-------------------------------------*/
+
+use crate::bitset::{VertexSet, WideSet};
+use crate::cycle::Cycle;
+use crate::error::Error;
+use crate::lattice::Lattice;
+use crate::metrics::Metrics;
+use crate::search::{arrive, initialize_state, leave, may_visit, Arrival, SearchState};
+
+struct Frame<S> {
+    candidates: S,
+    opened_return_edges: usize,
+}
+
+/// An iterator over the solutions of a lattice, searched for one at a time as they are asked for.
+///
+/// The solutions come in the same order as from `check_board`.
+pub struct Cycles {
+    walk: Walks,
+}
+
+/// The search, in the sets that fit the size of the lattice (as in `initialize_board`)
+enum Walks {
+    Narrow(Walk<u128>),
+    Wide(Walk<WideSet>),
+}
+
+struct Walk<S: VertexSet> {
+    board: SearchState<S>,
+    solution_path: Vec<usize>,
+    stack: Vec<Frame<S>>,
+    metrics: Metrics,
+}
+
+impl Cycles {
+    pub fn new(lattice: &Lattice) -> Result<Self, Error> {
+        let walk = if lattice.size() <= u128::CAPACITY {
+            Walks::Narrow(Walk::new(lattice))
+        } else {
+            Walks::Wide(Walk::new(lattice))
+        };
+        Ok(Cycles { walk })
+    }
+
+    /// The counters of the search so far
+    pub fn metrics(&self) -> &Metrics {
+        match &self.walk {
+            Walks::Narrow(walk) => &walk.metrics,
+            Walks::Wide(walk) => &walk.metrics,
+        }
+    }
+}
+
+impl<S: VertexSet> Walk<S> {
+    fn new(lattice: &Lattice) -> Self {
+        let mut walk = Walk {
+            board: initialize_state(lattice),
+            solution_path: vec![],
+            stack: vec![],
+            metrics: Metrics::new(),
+        };
+        // start with vertice 0 (there are at least 12 vertices, so the path does not end there)
+        if arrive(&mut walk.board, &mut walk.solution_path, 0, &mut walk.metrics) == Arrival::Continue {
+            walk.push_frame();
+        }
+        walk
+    }
+
+    fn push_frame(&mut self) {
+        let v = self.solution_path[self.solution_path.len() - 1];
+        self.stack.push(Frame {
+            candidates: self.board.edges[v].and_not(&self.board.visited),
+            opened_return_edges: self.board.return_edges.len(),
+        });
+    }
+
+    fn next_cycle(&mut self) -> Option<Cycle> {
+        while let Some(frame) = self.stack.last_mut() {
+            let i = match frame.candidates.first() {
+                Some(i) => i,
+                None => {
+                    // every way on from the last vertice has been tried - backtrack
+                    let opened_return_edges = frame.opened_return_edges;
+                    self.stack.pop();
+                    leave(&mut self.board, &mut self.solution_path, opened_return_edges, &mut self.metrics);
+                    if !self.stack.is_empty() {
+                        self.metrics.visited_vertices -= 1;
+                    }
+                    continue;
+                }
+            };
+            frame.candidates.remove(i);
+            if !may_visit(&mut self.board, &self.solution_path, i, &self.metrics) {
+                continue;
+            }
+            self.metrics.visited_vertices += 1;
+            match arrive(&mut self.board, &mut self.solution_path, i, &mut self.metrics) {
+                Arrival::DeadEnd => self.metrics.visited_vertices -= 1,
+                Arrival::Solution(weight) => {
+                    self.metrics.visited_vertices -= 1;
+                    self.metrics.solutions_counter += weight;
+                    let mut vertices = self.solution_path.clone();
+                    vertices.push(i);
+                    return Some(Cycle::new(vertices));
+                }
+                Arrival::Continue => self.push_frame(),
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for Cycles {
+    type Item = Cycle;
+
+    fn next(&mut self) -> Option<Cycle> {
+        match &mut self.walk {
+            Walks::Narrow(walk) => walk.next_cycle(),
+            Walks::Wide(walk) => walk.next_cycle(),
+        }
+    }
+}
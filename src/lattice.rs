@@ -0,0 +1,88 @@
+use crate::error::Error;
+use crate::iter::Cycles;
+
+/// An n x m dot matrix.
+///
+/// Vertices are numbered row by row: vertex `j*n + i` is in column i of row j,
+/// so vertex 0 is the top left corner and `n*m - 1` the bottom right corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lattice {
+    n: usize,
+    m: usize,
+}
+
+impl Lattice {
+    /// A lattice with n columns and m rows, if the size passes `validate_board_size`
+    pub fn new(n: usize, m: usize) -> Result<Self, Error> {
+        validate_board_size(n, m)?;
+        Ok(Lattice { n, m })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    /// Number of vertices (n * m)
+    pub fn size(&self) -> usize {
+        self.n * self.m
+    }
+
+    /// Are a and b next to each other, in the same row or the same column?
+    pub fn are_neighbours(&self, a: usize, b: usize) -> bool {
+        let n = self.n;
+        let (low, high) = (a.min(b), a.max(b));
+        high < self.size() && (high - low == n || (high - low == 1 && high % n != 0))
+    }
+
+    /// All rim vertices in clockwise direction, starting with vertex 0 (there are 2n+2m-4 rim vertices)
+    pub fn rim_vertices(&self) -> Vec<usize> {
+        let (n, m) = (self.n, self.m);
+        let mut rim_vertices = Vec::with_capacity(2 * n + 2 * m - 4);
+        for i in 0..n {
+            rim_vertices.push(i);
+        }
+        for j in 1..m {
+            rim_vertices.push((j+1)*n - 1);
+        }
+        for i in 1..n {
+            rim_vertices.push(n*m-1 - i);
+        }
+        for j in 1..m-1 {
+            rim_vertices.push(n*(m-1) - j*n);
+        }
+        rim_vertices
+    }
+
+    /// The solutions of the lattice, searched for one at a time as the iterator is advanced
+    ///
+    /// ```
+    /// # use roundtrip::Lattice;
+    /// let lattice = Lattice::new(4, 4)?;
+    /// for cycle in lattice.cycles()? {
+    ///     assert_eq!(cycle.vertices.len(), 16);
+    /// }
+    /// assert_eq!(lattice.cycles()?.count(), 6);
+    /// # Ok::<(), roundtrip::Error>(())
+    /// ```
+    pub fn cycles(&self) -> Result<Cycles, Error> {
+        Cycles::new(self)
+    }
+}
+
+pub fn validate_board_size(n: usize, m: usize) -> Result<(), Error> {
+    if n > m {
+        // enforce that N <= M (an NxM matrix have same solutions as a MxN matrix, so this is just to be able to assert a 'high and thin' matrix when checking and printing solutions)
+        return Err(Error::NotHighAndThin { n, m });
+    }
+    if n * m < 12 {
+        Err(Error::TooSmall { n, m })
+    } else if (n * m) & 1 == 1 {
+        Err(Error::OddSize { n, m })
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+/*-------------------------------------
+  RoundTrip
+
+    Consider this puzzle
+    The readers is presented with a board consisting of a n x m dot matrix and some lines connecting some of the dots.
+    Can you find a way to connect all the dots with a closed loop, including the original lines?
+
+    The original puzzle has 8x8 dots, but here we are going to consider the general case with n x m dots.
+    If we connect all the dots with horizontal and vertical lines they form a  pattern of (n-1)x(m-1) square tiles.
+    We call the dots 'vertices' and the lines 'edges'. Only horizontal and vertical edges are allowed.
+    The problem: To connect ALL vertices so that the edges form a closed route traversing all vertices ONCE.
+
+    If you play around with this a little bit you will soon discover that
+     - each vertice must be connected with exactly two other vertices via exactly two edges
+     - it follows that the number of edges = number of vertices = n * m
+     - all corner vertices (top left/right and bottom left/right) have only two neighbours (no diagonal edges allowed)
+        and must therefore have edges connecting them
+     - the number of horizontal lines (edges) must be even, as must the number of vertical lines (edges)
+     - from this it follows that n x m must be an even number and hence; n and/or m must be even
+        (3x3, 5x5, 3x7 etc. has no solution - please feel free to try!)
+
+    Other facts can be observed about the tiles when a valic closed loop has been established:
+     - the number of tiles outside the loop = (n-2)*(m-2)/2
+        (for example an 8 x 8 matrix will have (8-2)*(8-2)/2 = 18 tiles outside the loop )
+     - it follows that the number of tiles inside the loop = (n-1)*(m-1) - (n-2)*(m-2)/2
+        (for 8x8 matrix: (8-1)*(8-1) - 18 = 49-18 = 31 tiles will be inside the loop)
+     - all corner tiles must be inside the loop
+     - any 2x2 sub-matrix must have at least one tile outside the loop and one tile inside the loop
+     - no 2x2 sub-matrix can have a checker-pattern with tiles inside and outside the loop
+        (imagine coloring tiles inside the loop black and tiles outside the loop white)
+     - two neighbour tiles at the rim of the matrix cannot both be outside the loop
+
+    I originally came across this puzzle in a newspaper, and the reader was asked to complete the loop in a 8 x 8 matrix
+    where a handfull of edges had already been established. The original state was given such that there was only one
+    way to complete the loop according to the rules. After solving a few of these puzzles I started to consider some
+    mathematical properties connected with the puzzle.
+
+    First of all, I wondered how many different solutions could be created if we didn't put any edges into the start state.
+    Then I started considering different matrix sizes. Is there a formula for the number of variations for an n x m matrix?
+    It is pretty easy to prove that nxm has to be an even number. That is; there are no solutions to a n x m matrix when
+    both n and m are odd. Next, I explored small matrices by hand. It became obvious that the number of solutions
+    grew very quickly as n and m increased. But I was not able to see any obvious pattern  from the small examples I could
+    calculate by hand. So I wrote a computer program to check for solutions for larger matrices.
+
+    My first attempt was based on tilings of the (n-1) x (m-1) matrix. I soon found that the rule for solutions to the
+    2 x m matrices formed the series 	1, 3, 4, 10, 16, 36, 64, 136, 256, 528, 1024, 2080, 4096... which I found in the
+    on-line encyclopedia of integer sequences (https://oeis.org/) as the series A051437. However, none of the other
+    dimensions seemed to give me any hits. This strikes me as strange, as I feel that this problem should follow some
+    'known' combinatoric pattern.
+
+    So here I set out to explore more. Maybe my first program had an error? Maybe I wasn't able to remove duplicates?
+    Or maybe I should consider remove solutions that are identical when rotating the matrix? What about 'flipped'
+    solutions - should they be counted as different or not? But most of all - was my first attempt not really equivalent
+    to the original problem of finding the edges that form a closed loop including all vertices?
+
+    In this program we set out to search the solution space by traveling from an original vertice through edges to neighbouring
+    edges until we find all possible versions ending with a valid solution (n*m edges in a closed loop visiting all vertices).
+    The algorithm aims to find all possible paths. And this time we don't care if we get patterns that are 'equal' when rotated. 
+    
+    Brønnøysund, 9.9.2020
+    Torgeir Kruke
+
+    v0.1 - project created and some initiating code in place
+    v0.2 - first version generating solutions for matrices up to about 6x6
+    v0.3 - using box'ing to avoid stack usage (thanks Anders :)
+    v0.4 - non-interactive command line interface (roundtrip count n m) for scripted runs
+    v0.5 - search engine split into the roundtrip library; the program is a thin wrapper around it
+    v0.6 - transfer matrix counter, and boards allocated at run time (no more N_MAX and M_MAX)
+    v0.7 - search state kept in u128 bitsets instead of the n*m x n*m adjacency matrix
+    v0.8 - search split over several threads
+    v0.9 - checkpoints, so long runs can be resumed
+    v0.10 - puzzle mode: boards with edges already drawn, as in the newspaper
+    v0.11 - puzzles and solutions read and written as text files
+    v0.12 - the solutions found can be listed, not just counted
+    v0.13 - solution visitors: what happens to each solution is up to the caller
+    v0.14 - solutions can be pulled one at a time from an iterator (lattice.cycles())
+    v0.15 - solutions, puzzles and partial paths drawn with box drawing characters
+    v0.16 - solutions and puzzles drawn as SVG images
+    v0.17 - JSON reports of the results and metrics of a run
+    v0.18 - sweeps over ranges of board sizes, written as CSV or JSON tables
+    v0.19 - a brute force reference counter to check the search against
+    v0.20 - verification mode: the closure of every loop found is checked after all
+    v0.21 - solutions counted up to rotations and reflections as well (Burnside's lemma)
+    v0.22 - only one solution of every such class listed, if asked for
+    v0.23 - the solutions left unchanged by a given rotation or reflection searched for directly
+    v0.24 - rotated and mirrored copies counted from one of them instead of searched for (less than half the checks)
+    v0.25 - the search backs up as soon as the path cuts the unvisited vertices apart (a third of the checks on 8x8)
+    v0.26 - boards over 128 vertices searched again, with their sets of vertices in as many 64 bit words as needed
+
+
+-------------------------------------*/
+
+//! Counting Hamiltonian cycles (closed loops visiting every dot once) on n x m lattices.
+//!
+//! ```
+//! let count = roundtrip::count_cycles(4, 4).unwrap();
+//! assert_eq!(count.to_string(), "6");
+//! ```
+
+mod bitset;
+mod checkpoint;
+mod cycle;
+mod error;
+mod grid;
+mod iter;
+mod lattice;
+mod metrics;
+mod parallel;
+mod puzzle;
+mod reference;
+mod render;
+mod report;
+mod search;
+mod svg;
+mod sweep;
+mod symmetry;
+mod transfer;
+mod visitor;
+
+pub use checkpoint::{count_with_checkpoints, resume_from_checkpoint, Checkpoint};
+pub use cycle::{Cycle, Solutions};
+pub use error::Error;
+pub use grid::{parse_puzzle, write_puzzle, write_solution};
+pub use iter::Cycles;
+pub use lattice::{validate_board_size, Lattice};
+pub use metrics::Metrics;
+pub use parallel::{check_positions, count_parallel, default_split_depth};
+pub use puzzle::Puzzle;
+pub use reference::{count_reference, cross_check, Mismatch};
+pub use render::{render_cycle, render_path, render_puzzle};
+pub use report::Report;
+pub use search::{
+    check_board, check_position, collect_positions, count_board, count_with_metrics, count_with_visitor, find_cycles, initialize_board,
+    verify_with_visitor, Board, SearchPosition,
+};
+pub use svg::{svg_puzzle, svg_sheet, svg_solution, SvgStyle};
+pub use sweep::{Outcome, Sweep, SweepRow, SWEEP_CSV_HEADER};
+pub use symmetry::{edge_bits, is_canonical, CountSymmetric, OnlyCanonical, Symmetry};
+pub use transfer::{count_transfer_matrix, MAX_FRONTIER_WIDTH};
+pub use visitor::{CollectCycles, CountSolutions, ReportProgress, SolutionVisitor, StopAfter, Visit, WriteCycles};
+
+/// The number of solutions found for a board.
+///
+/// Counts grow super-exponentially with the board size, so they are carried as u128,
+/// or as an arbitrary precision integer when the `bigint` feature is enabled.
+#[cfg(not(feature = "bigint"))]
+pub type Count = u128;
+#[cfg(feature = "bigint")]
+pub type Count = num_bigint::BigUint;
+
+/// The counting engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Depth first search following the path vertice by vertice (`check_board`)
+    Search,
+    /// Row by row transfer matrix over the connectivity of the frontier (`count_transfer_matrix`)
+    TransferMatrix,
+    /// Every path from vertice 0, without any pruning (`count_reference`, for checking the others on small boards)
+    Reference,
+}
+
+impl Algorithm {
+    /// The name used on the command line and in reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Search => "search",
+            Algorithm::TransferMatrix => "transfer",
+            Algorithm::Reference => "reference",
+        }
+    }
+}
+
+/// Count the closed loops visiting all vertices of an n x m lattice
+pub fn count_cycles(n: usize, m: usize) -> Result<Count, Error> {
+    count_cycles_with(n, m, Algorithm::Search)
+}
+
+/// Count the closed loops visiting all vertices of an n x m lattice using the given algorithm
+pub fn count_cycles_with(n: usize, m: usize, algorithm: Algorithm) -> Result<Count, Error> {
+    let lattice = Lattice::new(n, m)?;
+    Ok(match algorithm {
+        Algorithm::Search => count_with_metrics(&lattice, &mut Metrics::new())?,
+        Algorithm::TransferMatrix => count_transfer_matrix(&lattice)?,
+        Algorithm::Reference => count_reference(&lattice)?,
+    })
+}
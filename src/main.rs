@@ -1,105 +1,130 @@
 /*-------------------------------------
-  RoundTrip
-
-    Consider this puzzle
-    The readers is presented with a board consisting of a n x m dot matrix and some lines connecting some of the dots.
-    Can you find a way to connect all the dots with a closed loop, including the original lines?
-
-    The original puzzle has 8x8 dots, but here we are going to consider the general case with n x m dots.
-    If we connect all the dots with horizontal and vertical lines they form a  pattern of (n-1)x(m-1) square tiles.
-    We call the dots 'vertices' and the lines 'edges'. Only horizontal and vertical edges are allowed.
-    The problem: To connect ALL vertices so that the edges form a closed route traversing all vertices ONCE.
-
-    If you play around with this a little bit you will soon discover that
-     - each vertice must be connected with exactly two other vertices via exactly two edges
-     - it follows that the number of edges = number of vertices = n * m
-     - all corner vertices (top left/right and bottom left/right) have only two neighbours (no diagonal edges allowed)
-        and must therefore have edges connecting them
-     - the number of horizontal lines (edges) must be even, as must the number of vertical lines (edges)
-     - from this it follows that n x m must be an even number and hence; n and/or m must be even
-        (3x3, 5x5, 3x7 etc. has no solution - please feel free to try!)
-
-    Other facts can be observed about the tiles when a valic closed loop has been established:
-     - the number of tiles outside the loop = (n-2)*(m-2)/2
-        (for example an 8 x 8 matrix will have (8-2)*(8-2)/2 = 18 tiles outside the loop )
-     - it follows that the number of tiles inside the loop = (n-1)*(m-1) - (n-2)*(m-2)/2
-        (for 8x8 matrix: (8-1)*(8-1) - 18 = 49-18 = 31 tiles will be inside the loop)
-     - all corner tiles must be inside the loop
-     - any 2x2 sub-matrix must have at least one tile outside the loop and one tile inside the loop
-     - no 2x2 sub-matrix can have a checker-pattern with tiles inside and outside the loop
-        (imagine coloring tiles inside the loop black and tiles outside the loop white)
-     - two neighbour tiles at the rim of the matrix cannot both be outside the loop
-
-    I originally came across this puzzle in a newspaper, and the reader was asked to complete the loop in a 8 x 8 matrix
-    where a handfull of edges had already been established. The original state was given such that there was only one
-    way to complete the loop according to the rules. After solving a few of these puzzles I started to consider some
-    mathematical properties connected with the puzzle.
-
-    First of all, I wondered how many different solutions could be created if we didn't put any edges into the start state.
-    Then I started considering different matrix sizes. Is there a formula for the number of variations for an n x m matrix?
-    It is pretty easy to prove that nxm has to be an even number. That is; there are no solutions to a n x m matrix when
-    both n and m are odd. Next, I explored small matrices by hand. It became obvious that the number of solutions
-    grew very quickly as n and m increased. But I was not able to see any obvious pattern  from the small examples I could
-    calculate by hand. So I wrote a computer program to check for solutions for larger matrices.
-
-    My first attempt was based on tilings of the (n-1) x (m-1) matrix. I soon found that the rule for solutions to the
-    2 x m matrices formed the series 	1, 3, 4, 10, 16, 36, 64, 136, 256, 528, 1024, 2080, 4096... which I found in the
-    on-line encyclopedia of integer sequences (https://oeis.org/) as the series A051437. However, none of the other
-    dimensions seemed to give me any hits. This strikes me as strange, as I feel that this problem should follow some
-    'known' combinatoric pattern.
-
-    So here I set out to explore more. Maybe my first program had an error? Maybe I wasn't able to remove duplicates?
-    Or maybe I should consider remove solutions that are identical when rotating the matrix? What about 'flipped'
-    solutions - should they be counted as different or not? But most of all - was my first attempt not really equivalent
-    to the original problem of finding the edges that form a closed loop including all vertices?
-
-    In this program we set out to search the solution space by traveling from an original vertice through edges to neighbouring
-    edges until we find all possible versions ending with a valid solution (n*m edges in a closed loop visiting all vertices).
-    The algorithm aims to find all possible paths. And this time we don't care if we get patterns that are 'equal' when rotated. 
-    
-    Brønnøysund, 9.9.2020
-    Torgeir Kruke
-
-    v0.1 - project created and some initiating code in place
-    v0.2 - first version generating solutions for matrices up to about 6x6
-    v0.3 - using box'ing to avoid stack usage (thanks Anders :)
+  RoundTrip - command line front end to the roundtrip library
+-------------------------------------*/
 
+use std::env;
+use std::fs;
+use std::io::{self, stdin, stdout, BufWriter, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
--------------------------------------*/
+use roundtrip::{
+    count_board, count_parallel, count_reference, count_transfer_matrix, count_with_checkpoints, cross_check, default_split_depth,
+    initialize_board, parse_puzzle, render_cycle, render_puzzle, resume_from_checkpoint, svg_sheet, write_solution, Algorithm,
+    CollectCycles, Count, CountSymmetric, Cycle, Error, Lattice, Metrics, OnlyCanonical, Outcome, Puzzle, Report, ReportProgress,
+    SolutionVisitor, SvgStyle, Sweep, SweepRow, Symmetry, WriteCycles, SWEEP_CSV_HEADER,
+};
+
+// exit codes reported to the shell when running non-interactively
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_FAILURE: i32 = 1;          // invalid board size or checkpoint, the count could not be completed or crosscheck failed
+const EXIT_USAGE: i32 = 2;            // the command line could not be parsed
+
+const USAGE: &str = "\
+Usage: roundtrip [OPTIONS] [COMMAND]
+
+Commands:
+  count <N> <M>    Count the closed loops visiting all dots of an N x M board
+  resume <FILE>    Continue a count from a checkpoint file
+  sweep <N> <M>    Count every board with n and m in the ranges N and M (such as 2..8), as a table
+  crosscheck <N> <M>
+                   Count every board up to N x M with both the search and the reference counter,
+                   and report the sizes where they disagree
+  solve <FILE>     Complete the puzzle in FILE, drawn with '.' dots, '-' and '|' edges and 'x' forbidden edges
+  help             Print this message
 
-use std::io::{stdin, stdout, Write};
-use std::time::SystemTime;
-
-// adjust N_MAX and M_MAX equal to n and m if you want to optimize for memory usage (and possibly speed)
-const N_MAX: usize = 20;
-const M_MAX: usize = 20;
-
-struct Metrics {
-    pub run_duration: SystemTime,
-    pub check_counter: i64,
-    pub fail_counter_1: i64,
-    pub fail_counter_2: i64,
-    pub fail_counter_3: i64,
-    pub exception_counter: i64,
-    pub solutions_counter: i64,
-    pub visited_vertices: usize,
-    pub visited_rim_vertices: usize,
+Without a command the program asks for board sizes interactively.
+
+Options:
+  -a, --algorithm <ALG>    Counting engine: search (default), transfer (transfer matrix, for large boards)
+                           or reference (every path, without pruning - small boards only)
+  -t, --threads <N>        Number of threads for the search (default 1)
+      --split-depth <D>    Path length where the search is split between threads (default n*m/4)
+      --checkpoint <FILE>  Save the state of the search to FILE, so it can be resumed
+      --checkpoint-interval <SECONDS>
+                           Time between checkpoints (default 60)
+  -c, --cycles             Also list the solutions counted, as the vertices visited from 0 and back
+                           (count and sweep only, on a single thread)
+      --verify             Check that every loop counted by the search closes and is a valid cycle
+                           (count only, on a single thread)
+      --classes            Also count the solutions that are different up to rotations and reflections
+                           (count only, on a single thread)
+      --symmetric <SYM>    Only count (and list) the solutions left unchanged by SYM: half-turn, left-right or
+                           top-bottom (mirror), or on square boards also quarter-turn, diagonal or anti-diagonal
+                           (count only, on a single thread)
+      --no-folding         Search the rotated and mirrored copies of the loops too, instead of counting them from one of them
+                           (count and sweep only, on a single thread)
+      --no-pruning         Go on even where the path cuts the unvisited dots apart (to measure what the check saves)
+                           (count and sweep only, on a single thread)
+      --canonical          List only one solution of every class of rotated and flipped copies
+                           (implies --cycles and --classes)
+  -d, --draw               List the solutions (and the puzzle solved) as box drawings
+      --shade              Shade (or fill) the tiles inside the loop in the drawings
+      --svg <FILE>         Also draw the solutions listed (or the puzzle, if none) as an SVG image
+      --cell-size <PX>     Distance between the dots in the SVG image (default 24)
+      --budget <SECONDS>   Time allowed per board in a sweep; larger boards with the same n are skipped after it
+      --max-solutions <K>  Number of solutions listed by count --cycles or solve (default 100)
+  -o, --output <FILE>      Write the solutions listed (or the sweep table) to FILE instead of printing them
+  -f, --format <FORMAT>    Output format: text (default), count (just the number), json (a report of the run)
+                           or csv (sweep tables only)
+  -q, --quiet              Only print the result
+  -v, --verbose            Print board initialization and search progress
+  -h, --help               Print this message
+
+Exit codes:
+  0  success
+  1  invalid board size or checkpoint, the count could not be completed, or crosscheck found a mismatch
+  2  invalid command line";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Count,
+    Json,
+    Csv,
 }
 
-impl Metrics {
-    pub fn new () -> Self {
-        Metrics {
-            run_duration: SystemTime::now(),
-            check_counter: 0,
-            fail_counter_1: 0,
-            fail_counter_2: 0,
-            fail_counter_3: 0,
-            exception_counter: 0,
-            solutions_counter: 0,
-            visited_vertices: 0,
-            visited_rim_vertices: 0,
-        }
-    }
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+struct Options {
+    algorithm: Algorithm,
+    threads: usize,
+    split_depth: Option<usize>,
+    checkpoint: Option<PathBuf>,
+    checkpoint_interval: Duration,
+    cycles: bool,
+    verify: bool,
+    classes: bool,
+    canonical: bool,
+    symmetry: Option<Symmetry>,
+    fold: bool,
+    prune: bool,
+    draw: bool,
+    shade: bool,
+    svg: Option<PathBuf>,
+    cell_size: usize,
+    budget: Option<Duration>,
+    max_solutions: usize,
+    output: Option<PathBuf>,
+    format: Format,
+    verbosity: Verbosity,
+}
+
+enum Command {
+    Interactive,
+    Count { n: usize, m: usize },
+    Sweep { n: RangeInclusive<usize>, m: RangeInclusive<usize> },
+    CrossCheck { n: usize, m: usize },
+    Resume { checkpoint: PathBuf },
+    Solve { puzzle: PathBuf },
+    Help,
 }
 
 fn get_matrix_dimension() -> (usize, usize) {
@@ -108,7 +133,7 @@ fn get_matrix_dimension() -> (usize, usize) {
         let mut input_m = "".to_string();
         println!("Enter matrix size n x m (or 0 to end)");
         print!("N: ");
-        stdout().flush().ok().expect("Could not flush stdout");
+        stdout().flush().expect("Could not flush stdout");
         stdin()
             .read_line(&mut input_n)
             .expect("Could not read line");
@@ -121,7 +146,7 @@ fn get_matrix_dimension() -> (usize, usize) {
             }
         };
         print!("M: ");
-        stdout().flush().ok().expect("Could not flush stdout");
+        stdout().flush().expect("Could not flush stdout");
         stdin()
             .read_line(&mut input_m)
             .expect("Could not read line");
@@ -137,304 +162,639 @@ fn get_matrix_dimension() -> (usize, usize) {
     }
 }
 
-fn validate_board_size(n: usize, m: usize) -> bool {
-    if n > N_MAX || m > M_MAX {
-        println!("n and m must be less or equal to {} and {}", N_MAX, M_MAX);
-        return false;
-    }
-    if n > m {
-        // enforce that N <= M (an NxM matrix have same solutions as a MxN matrix, so this is just to be able to assert a 'high and thin' matrix when checking and printing solutions)
-        println!("n ({}) should be less or equal to m ({})", n, m);
-        return false;
-    }
-    let size = n * m;
-    if size < 12 {
-        println!("Too small!");
-        println!("Board size n*m must be min 12 and max 128.");
-        return false;
-    } else if size > 128 {
-        println!("Too big!");
-        println!("Board size n*m must be min 12 and max 128.");
-        return false;
-    } else if (n * m) & 1 == 1 {
-        println!("Invalid matrix size");
-        println!("n * m MUST be an even number");
-        return false;
-    } else {
-        return true;
+fn parse_number(name: &str, value: Option<&String>) -> Result<usize, String> {
+    match value {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| format!("Could not assign a value to {}: {:?}", name, value)),
+        None => Err(format!("Missing value for {}", name)),
+    }
+}
+
+/// A range of sizes, `A..B` (both included), or a single size `A`
+fn parse_range(name: &str, value: Option<&String>) -> Result<RangeInclusive<usize>, String> {
+    match value.and_then(|value| value.split_once("..")) {
+        Some((from, to)) => {
+            let from = parse_number(name, Some(&from.to_string()))?;
+            let to = parse_number(name, Some(&to.to_string()))?;
+            Ok(from..=to)
+        }
+        None => {
+            let size = parse_number(name, value)?;
+            Ok(size..=size)
+        }
     }
 }
 
-fn initialize_board(board: &mut [[bool; N_MAX * M_MAX]; N_MAX * M_MAX], rim_vertices: &mut Vec<usize>, n: usize, m: usize, )  {
-    // Initialize the adjecency matrix
-    // The diagonal b[i,i] will indicate if vertice i has been visited (true) or not (false)
-    // For the rest of the matrix b[i][j] = true indicates that there is an edge from vertice i to j
-    // If there is an edge both from i to j and from j to i, the edge betweed i and j is undirected
-    for j in 0..n * m {
-        for i in 0..n * m {
-            // println!("i, j = {}, {}", i, j);
-            if (j >= n && (i == j-n))                        // i has j as its neighbour below
-                || (j > 0 && (i == j-1 && ((i+1) % n != 0))) // i has j as its neighbour to the right (check that i is not at the right rim) 
-                || (i == j+1 && (i % n != 0))                // i has j as its neighbour to the left (check that i is not at the left hand rim)
-                || (i == j+n)                                // i has j as its neighbour above
-            {
-                board[i][j] = true;
-                // b[j][i] = true;  
-                    // this is surperflous since we traverse all nodes and include all valid neighbours; 
-                    // hence j -> i will also always be covered
+fn parse_args(args: &[String]) -> Result<(Command, Options), String> {
+    let mut options = Options {
+        algorithm: Algorithm::Search,
+        threads: 1,
+        split_depth: None,
+        checkpoint: None,
+        checkpoint_interval: Duration::from_secs(60),
+        cycles: false,
+        verify: false,
+        classes: false,
+        canonical: false,
+        symmetry: None,
+        fold: true,
+        prune: true,
+        draw: false,
+        shade: false,
+        svg: None,
+        cell_size: 24,
+        budget: None,
+        max_solutions: 100,
+        output: None,
+        format: Format::Text,
+        verbosity: Verbosity::Normal,
+    };
+    let mut positional: Vec<&String> = vec![];
+    let mut k = 0;
+    while k < args.len() {
+        match args[k].as_str() {
+            "-f" | "--format" => {
+                k += 1;
+                options.format = match args.get(k).map(|f| f.as_str()) {
+                    Some("text") => Format::Text,
+                    Some("count") => Format::Count,
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    Some(other) => return Err(format!("Unknown output format: {:?}", other)),
+                    None => return Err("Missing value for --format".to_string()),
+                };
+            }
+            "-a" | "--algorithm" => {
+                k += 1;
+                options.algorithm = match args.get(k).map(|a| a.as_str()) {
+                    Some("search") => Algorithm::Search,
+                    Some("transfer") => Algorithm::TransferMatrix,
+                    Some("reference") => Algorithm::Reference,
+                    Some(other) => return Err(format!("Unknown algorithm: {:?}", other)),
+                    None => return Err("Missing value for --algorithm".to_string()),
+                };
+            }
+            "-t" | "--threads" => {
+                k += 1;
+                options.threads = parse_number("--threads", args.get(k))?;
+                if options.threads == 0 {
+                    return Err("--threads must be at least 1".to_string());
+                }
             }
+            "--split-depth" => {
+                k += 1;
+                options.split_depth = Some(parse_number("--split-depth", args.get(k))?);
+            }
+            "--checkpoint" => {
+                k += 1;
+                let file = args.get(k).ok_or("Missing value for --checkpoint")?;
+                options.checkpoint = Some(PathBuf::from(file));
+            }
+            "--checkpoint-interval" => {
+                k += 1;
+                let seconds = parse_number("--checkpoint-interval", args.get(k))?;
+                options.checkpoint_interval = Duration::from_secs(seconds as u64);
+            }
+            "-c" | "--cycles" => options.cycles = true,
+            "--verify" => options.verify = true,
+            "--classes" => options.classes = true,
+            "--symmetric" => {
+                k += 1;
+                let name = args.get(k).ok_or("Missing value for --symmetric")?;
+                match Symmetry::ALL.iter().find(|symmetry| symmetry.name() == name) {
+                    Some(&symmetry) => options.symmetry = Some(symmetry),
+                    None => return Err(format!("Unknown symmetry: {:?}", name)),
+                }
+            }
+            "--no-folding" => options.fold = false,
+            "--no-pruning" => options.prune = false,
+            "--canonical" => {
+                options.canonical = true;
+                options.cycles = true;
+                options.classes = true;
+            }
+            "-d" | "--draw" => options.draw = true,
+            "--shade" => options.shade = true,
+            "--svg" => {
+                k += 1;
+                let file = args.get(k).ok_or("Missing value for --svg")?;
+                options.svg = Some(PathBuf::from(file));
+            }
+            "--cell-size" => {
+                k += 1;
+                options.cell_size = parse_number("--cell-size", args.get(k))?;
+            }
+            "--budget" => {
+                k += 1;
+                let seconds = parse_number("--budget", args.get(k))?;
+                options.budget = Some(Duration::from_secs(seconds as u64));
+            }
+            "--max-solutions" => {
+                k += 1;
+                options.max_solutions = parse_number("--max-solutions", args.get(k))?;
+            }
+            "-o" | "--output" => {
+                k += 1;
+                let file = args.get(k).ok_or("Missing value for --output")?;
+                options.output = Some(PathBuf::from(file));
+            }
+            "-q" | "--quiet" => options.verbosity = Verbosity::Quiet,
+            "-v" | "--verbose" => options.verbosity = Verbosity::Verbose,
+            "-h" | "--help" => return Ok((Command::Help, options)),
+            arg if arg.starts_with('-') && arg.len() > 1 => return Err(format!("Unknown option: {}", arg)),
+            _ => positional.push(&args[k]),
         }
+        k += 1;
     }
 
-    // Now enforce some special initialization of directed edges for the rim nodes:
-    //      (1) Edges can only be traveled from a rim node to the inside of the lattice (initially)
-    //              (we reverse this operation for one edge at a time - whenever we travel from a rim node to the interior of the lattice
-    //              we allow the path to return to the rim through the (clockwise) neighbour edge)
-    //  AND (2) Nodes at the rim can only be visited in a clockwise direction 
-    //              (hence no anti-clockwise edges between rim nodes)
-    //  Note: (2) enforces that we only find paths going in clockwise direction. Otherwise we would find all paths twice; once 
-    //        going in a clockwise direction and once going anti-clockwise. 
-    for i in 0..n-1 {
-        println!("i = {}", i);
-        board[i+n+1][i+1] = false;                  // from second row to top (first) row
-        board[n*(m-2)+i][n*(m-1)+i] = false;        // from second to last row to bottom (last) row
-        board[i+1][i] = false;                      // top row
-        board[n*(m-1)+i][n*(m-1)+i+1] = false;      // bottom row
-    }
-    for j in 0..m-1 {
-        println!("j = {}", j);
-        board[j*n+1][j*n] = false;                  // from second column to left rim (first) column
-        board[(j+2)*n - 2][(j+2)*n - 1] = false;    // from second to last column to right rim (last) column
-        board[j*n][(j+1)*n] = false;                // left rim column
-        board[(j+2)*n-1][(j+1)*n-1] = false;        // right rim column
-    }
-
-    // Make a vector with all rim nodes in clockwise direction sequence (there are 2n+2m-4 rim nodes)
-    for i in 0..n {
-        rim_vertices.push(i);
-    }
-    for j in 1..m {
-        rim_vertices.push((j+1)*n - 1);
-    }
-    for i in 1..n {
-        rim_vertices.push(n*m-1 - i);
-    } 
-    for j in 1..m-1 {
-        rim_vertices.push(n*(m-1) - j*n); 
-    }
-    println!("#rim vertices = {}", rim_vertices.len()+1);
-    for i in 0..rim_vertices.len() {
-        println!("rim_vertices[{}] = {}", i, rim_vertices[i]);
-    }
-
-    // println!("... finished initializing board");
-    return;
+    let command = match positional.first().map(|c| c.as_str()) {
+        None => Command::Interactive,
+        Some("help") => Command::Help,
+        Some("count") => {
+            if positional.len() > 3 {
+                return Err(format!("Unexpected argument: {}", positional[3]));
+            }
+            Command::Count {
+                n: parse_number("n", positional.get(1).copied())?,
+                m: parse_number("m", positional.get(2).copied())?,
+            }
+        }
+        Some("sweep") => {
+            if positional.len() > 3 {
+                return Err(format!("Unexpected argument: {}", positional[3]));
+            }
+            Command::Sweep {
+                n: parse_range("n", positional.get(1).copied())?,
+                m: parse_range("m", positional.get(2).copied())?,
+            }
+        }
+        Some("crosscheck") => {
+            if positional.len() > 3 {
+                return Err(format!("Unexpected argument: {}", positional[3]));
+            }
+            Command::CrossCheck {
+                n: parse_number("n", positional.get(1).copied())?,
+                m: parse_number("m", positional.get(2).copied())?,
+            }
+        }
+        Some("resume") => {
+            if positional.len() != 2 {
+                return Err("resume takes the checkpoint file as its only argument".to_string());
+            }
+            Command::Resume {
+                checkpoint: PathBuf::from(positional[1]),
+            }
+        }
+        Some("solve") => {
+            if positional.len() != 2 {
+                return Err("solve takes the puzzle file as its only argument".to_string());
+            }
+            Command::Solve {
+                puzzle: PathBuf::from(positional[1]),
+            }
+        }
+        Some(other) => return Err(format!("Unknown command: {}", other)),
+    };
+    if options.canonical && !matches!(command, Command::Count { .. }) {
+        return Err("--canonical lists the solutions of count".to_string());
+    }
+    // (sweep, solve, crosscheck and resume do not go through search(), so they would count all loops instead)
+    let counting = matches!(command, Command::Count { .. } | Command::Interactive);
+    if options.symmetry.is_some() && !counting {
+        return Err("--symmetric only restricts the solutions of count".to_string());
+    }
+    if options.verify && !counting {
+        return Err("--verify checks the loops found by count".to_string());
+    }
+    if options.classes && !counting {
+        return Err("--classes counts the classes of the solutions of count".to_string());
+    }
+    if (!options.fold || !options.prune) && !counting && !matches!(command, Command::Sweep { .. }) {
+        return Err("--no-folding and --no-pruning only change the search of count and sweep".to_string());
+    }
+    if options.cycles && (options.algorithm != Algorithm::Search || options.threads > 1 || options.checkpoint.is_some()) {
+        return Err("--cycles needs the search on a single thread, without checkpoints".to_string());
+    }
+    let single_thread = options.verify || options.classes || options.symmetry.is_some() || !options.fold || !options.prune;
+    if single_thread && (options.algorithm != Algorithm::Search || options.threads > 1 || options.checkpoint.is_some()) {
+        return Err("--verify, --classes, --symmetric, --no-folding and --no-pruning need the search on a single thread, without checkpoints".to_string());
+    }
+    if options.symmetry.is_some() && options.classes {
+        return Err("--symmetric can not be combined with --classes or --canonical".to_string());
+    }
+    let sweep = matches!(command, Command::Sweep { .. });
+    if sweep && (options.threads > 1 || options.checkpoint.is_some() || options.cycles || options.svg.is_some()) {
+        return Err("sweep runs the counter on a single thread, without checkpoints or listed solutions".to_string());
+    }
+    if options.format == Format::Csv && !sweep {
+        return Err("--format csv is only for sweep tables".to_string());
+    }
+    if options.svg.is_some() && !options.cycles && matches!(command, Command::Count { .. }) {
+        return Err("--svg draws the solutions listed by count --cycles".to_string());
+    }
+    Ok((command, options))
 }
 
-fn check_board(board: &mut [[bool; N_MAX * M_MAX]; N_MAX * M_MAX], 
-    rim_vertices: &Vec<usize>, 
-    // mut visited_rim_vertices: usize, 
-    // visited_vertices: usize, 
-    solution_path: &mut Vec<usize>, 
-    // solutions_counter: &mut i64, 
-    v: usize, 
-    n: usize, 
-    m: usize, 
-    metrics: &mut Metrics,)
-    // check_counter: &mut i64, 
-    // fail_counter_1: &mut i64,
-    // fail_counter_2: &mut i64,
-    // fail_counter_3: &mut i64,
-    // exception_counter: &mut i64,
-    // run_duration: &SystemTime) 
-    {
-    metrics.check_counter += 1;
-    // print!("{},", v);  // debug print
-    let at_the_rim = rim_vertices.contains(&v); 
-    if at_the_rim {
-        metrics.visited_rim_vertices += 1;
-        if metrics.visited_rim_vertices == rim_vertices.len() && metrics.visited_vertices + 1 < n*m {
-            metrics.fail_counter_2 += 1;
-            //println!("no more rim - backtrack, check_counter = {}", check_counter);  // debug print
-            //print!("-{},", v); // debug print
-            return;  // all rim vertices has been visited, but there remains unvisited interior vertices => fail!
-        } 
-    }
-    if metrics.visited_vertices + 1 == n * m {
-        //all vertices visited - can we make it back to the start vertice (0)?
-        // ToDo: Prove that if you get here you MUST have a solution - so, no need for a final check
-        //if board[v][0] {
-            // success!
-            // println!("... SOLUTION found!");
-            metrics.solutions_counter += 1;
-            //println!("solution #{}!", solutions_counter);
-            //print!("-{}", v);
-            if (metrics.solutions_counter + 1) % 10000 == 0 {
-                println!("{:?}: {} solutions", metrics.run_duration.elapsed(), metrics.solutions_counter+1);
-            }
-            return;
-        //} else {
-            // failure!
-        //    *fail_counter_1 += 1;
-            //println!("fail, check_counter = {}", check_counter);
-            //print!("-{}", v);
-        //    return;
-        //}
-    }
-
-    board[v][v] = true; // mark vertice v as visited
-    solution_path.push(v);
-    let mut store_j = 0;
-    let mut store_next_rim_vertice = 0;
-    for i in 0..n * m {
-        if board[v][i] && !board[i][i] {
-            // there is an edge from v to i, and vertice i has not been visited yet
-            let mut check_i = true;
-            if at_the_rim {
-                if !rim_vertices.contains(&i) { // we are about to enter the interior of the lattice...
-                    check_i = false;            // ... but we don't want to go to the interior unless we can set a return path to the rim
-                    // (this will happen when we are next to a corner vertice, or if we already visited the vertice providing the return edge)
-                    let next_rim_vertice = rim_vertices[metrics.visited_rim_vertices];
-                    for j in 0..n*m {
-                        if board[next_rim_vertice][j] && !rim_vertices.contains(&j) && !board[j][j] {
-                            board[j][next_rim_vertice] = true;  // we 'open' the return edge from the interior to the next rim vertice
-                            store_j = j; 
-                            store_next_rim_vertice = next_rim_vertice;
-                            check_i = true;  // return edge found - ok to continue 
-                            continue;
-                        }
-                    }
-                }
-            } else if !rim_vertices.contains(&i) {  // if we stay interior to the lattice...
-                //... then we can abort if we are about to create two separate 'islands' of unvisited vertices.
-                //  I.e. if we have unvisited vertices both left and right, while at least one of the vertices in front has been visited already.
-                //  This logic can be extended to incorporate cases when two regions are only connected through a single track
-                //  If so, we can only complete a cycle if we are in the opposite region to the one where our endpoint is.
-                //  In the special case where we approach the rim, the rim will act as such a single track connection and we have to go
-                //  to the left since the endpoint will always be to the right.
-                if v>i && v-i == n {
-                    // direction = 'n';
-                    if !board[v+n][v+n] && rim_vertices.contains(&(v-1)) && !board[v-1][v-1] {
-                        check_i = false;
-                    }   // "must go left" => cannot go this way
-                    if (board[i-n][i-n] || board[i-n-1][i-n-1] || board [i-n+1][i-n+1]) &&
-                        (!board[i-1][i-1] && !board[i+1][i+1]) {
-                            check_i = false;
-                    }
-                } else if v<i && i-v == n {
-                    // direction = 's';
-                    if !board[v-n][v-n] && rim_vertices.contains(&(v+1)) && !board[v+1][v+1] {
-                        check_i = false;
-                        //println!("south - must go left=north");
-                        //println!("v={}, i={}, board[v-n][v-n]={}, {}, solution_path:", v, i, board[v-n][v-n], rim_vertices.contains(&(v+1)));
-                        //for k in 0..solution_path.len() {
-                        //    print!("{},", solution_path[k]);
-                        //}
-                        //println!("");
-                    }   // "must go left" => cannot go this way
-                    if (board[i+n][i+n] || board[i+n-1][i+n-1] || board[i+n+1][i+n+1]) &&
-                        (!board[i-1][i-1] && !board[i+1][i+1]) {
-                            check_i = false;
-                    } 
-                } else if v>i && v-i == 1 {
-                    // direction = 'w';
-                    if !board[v+1][v+1] && rim_vertices.contains(&(v+n)) && !board[v+n][v+n] {
-                        check_i = false;
-                    }   // "must go left" => cannot go this way
-                    if (board[i-1][i-1] || board[i-1+n][i-1+n] || board[i-1-n][i-1-n]) &&
-                        (!board[i+n][i+n] && !board[i-n][i-n]) {
-                            check_i = false;
-                    }
-                } else if v<i && i-v == 1 {
-                    // direction = 'e';
-                    // If direction is 'east' we will always be going 'left'
-                    if (board[i+1][i+1] || board[i+1+n][i+1+n] || board[i+1-n][i+1-n]) &&
-                        (!board[i+n][i+n] && !board[i-n][i-n]) {
-                            check_i = false;
-                    }
+fn run_count(n: usize, m: usize, options: &Options) -> Result<(), Error> {
+    let lattice = Lattice::new(n, m)?;
+    let verbose = options.verbosity == Verbosity::Verbose;
+    let report = options.format == Format::Text && options.verbosity > Verbosity::Quiet;
+    if verbose {
+        let rim_vertices = lattice.rim_vertices();
+        println!("#rim vertices = {}", rim_vertices.len()+1);
+        for (i, rim_vertice) in rim_vertices.iter().enumerate() {
+            println!("rim_vertices[{}] = {}", i, rim_vertice);
+        }
+    }
+    if report {
+        match options.symmetry {
+            Some(symmetry) => println!("Searching solutions for {:?} x {:?} matrix left unchanged by the {}", n, m, symmetry),
+            None => println!("Searching solutions for {:?} x {:?} matrix", n, m),
+        }
+    }
+    let mut metrics = Metrics::new();
+    let mut progress = if verbose { Some(ReportProgress::default()) } else { None };
+    let mut symmetric = if options.classes { Some(CountSymmetric::new(&lattice)) } else { None };
+    let count = match options.algorithm {
+        Algorithm::Search if options.checkpoint.is_some() || options.threads > 1 => {
+            let split_depth = options.split_depth.unwrap_or_else(|| default_split_depth(&lattice));
+            match &options.checkpoint {
+                Some(checkpoint) => count_with_checkpoints(
+                    &lattice,
+                    &mut metrics,
+                    options.threads,
+                    split_depth,
+                    checkpoint,
+                    options.checkpoint_interval,
+                )?,
+                None => count_parallel(&lattice, &mut metrics, options.threads, split_depth)?,
+            }
+        }
+        Algorithm::Search if options.cycles => {
+            // the cycles are written as they are found, rather than kept until the end of the search
+            let (path, writer): (String, Box<dyn Write>) = match &options.output {
+                Some(output) => {
+                    let file = fs::File::create(output).map_err(|e| io_error(output, e))?;
+                    (output.display().to_string(), Box::new(BufWriter::new(file)))
                 }
+                None if options.format == Format::Text => ("stdout".to_string(), Box::new(stdout())),
+                None => ("".to_string(), Box::new(io::sink())),
+            };
+            let mut write = WriteCycles::new(writer, options.max_solutions);
+            if options.draw {
+                let shade = options.shade;
+                write = write.with_format(move |cycle| format!("{}\n", render_cycle(n, m, cycle, shade)));
+            }
+            let mut collect = options.svg.as_ref().map(|_| CollectCycles::new(options.max_solutions));
+            let listed = (&mut write, &mut collect);
+            let count = if options.canonical {
+                let listed = OnlyCanonical::new(&lattice, listed);
+                search(&lattice, &mut metrics, &mut (&mut progress, (&mut symmetric, listed)), options)?
+            } else {
+                search(&lattice, &mut metrics, &mut (&mut progress, (&mut symmetric, listed)), options)?
+            };
+            if let Some(e) = write.error.take() {
+                return Err(io_error(Path::new(&path), e));
+            }
+            write.writer.flush().map_err(|e| io_error(Path::new(&path), e))?;
+            if let Some(collect) = collect {
+                write_svg(&Puzzle::new(n, m)?, &collect.cycles, options)?;
+            }
+            count
+        }
+        Algorithm::Search => search(&lattice, &mut metrics, &mut (&mut progress, &mut symmetric), options)?,
+        Algorithm::TransferMatrix => count_transfer_matrix(&lattice)?,
+        Algorithm::Reference => count_reference(&lattice)?,
+    };
+    print_report(n, m, options.algorithm, count, symmetric.as_ref(), &metrics, options)
+}
+
+fn run_sweep(n: RangeInclusive<usize>, m: RangeInclusive<usize>, options: &Options) -> Result<(), Error> {
+    let sweep = Sweep {
+        n,
+        m,
+        algorithm: options.algorithm,
+        budget: options.budget,
+        folding: options.fold,
+        connectivity: options.prune,
+    };
+    let (path, mut writer): (String, Box<dyn Write>) = match &options.output {
+        Some(output) => {
+            let file = fs::File::create(output).map_err(|e| io_error(output, e))?;
+            (output.display().to_string(), Box::new(BufWriter::new(file)))
+        }
+        None => ("stdout".to_string(), Box::new(stdout())),
+    };
+    let head = match options.format {
+        Format::Csv => format!("{}\n", SWEEP_CSV_HEADER),
+        Format::Json => "[\n".to_string(),
+        Format::Text if options.verbosity > Verbosity::Quiet => {
+            format!("Sweeping {} board sizes with the {}\n", sweep.sizes().len(), options.algorithm.name())
+        }
+        _ => String::new(),
+    };
+    // the rows are written as they are counted, so a long sweep can be followed (and interrupted)
+    let mut result = writer.write_all(head.as_bytes());
+    let mut first = true;
+    sweep.run(|row| {
+        let line = match options.format {
+            Format::Csv => format!("{}\n", row.to_csv()),
+            Format::Json if first => format!("  {}", row.to_json()),
+            Format::Json => format!(",\n  {}", row.to_json()),
+            Format::Count => format!("{}\n", sweep_count(row)),
+            Format::Text => format!("{} x {}: {}\n", row.n, row.m, sweep_text(row)),
+        };
+        first = false;
+        if result.is_ok() {
+            result = writer.write_all(line.as_bytes()).and_then(|_| writer.flush());
+        }
+    });
+    if options.format == Format::Json {
+        result = result.and_then(|_| writer.write_all(if first { b"]\n" } else { b"\n]\n" }));
+    }
+    result.and_then(|_| writer.flush()).map_err(|e| io_error(Path::new(&path), e))
+}
+
+/// Count every board up to n x m with the search and the reference counter; Ok(false) if they disagree anywhere
+fn run_cross_check(n: usize, m: usize, options: &Options) -> Result<bool, Error> {
+    let report = options.format == Format::Text && options.verbosity > Verbosity::Quiet;
+    let mut sizes = 0;
+    let mismatches = cross_check(n, m, |n, m| {
+        sizes += 1;
+        if options.verbosity == Verbosity::Verbose {
+            println!("{} x {} checked", n, m);
+        }
+    })?;
+    for mismatch in &mismatches {
+        println!(
+            "{} x {}: the search counts {}, the reference counter {}",
+            mismatch.n, mismatch.m, mismatch.search, mismatch.reference
+        );
+    }
+    if report && mismatches.is_empty() {
+        println!("The search and the reference counter agree on all {} board sizes up to {} x {}", sizes, n, m);
+    }
+    Ok(mismatches.is_empty())
+}
+
+/// The count of a sweep row, or the reason there is none
+fn sweep_count(row: &SweepRow) -> String {
+    match &row.outcome {
+        Outcome::Counted(count) => count.to_string(),
+        outcome => outcome.status().to_string(),
+    }
+}
+
+fn sweep_text(row: &SweepRow) -> String {
+    match &row.outcome {
+        Outcome::Counted(count) => format!("{} solutions ({:?})", count, row.wall_time),
+        Outcome::TimedOut => format!("out of time after {:?}", row.wall_time),
+        Outcome::OverBudget => "skipped (a smaller board ran out of time)".to_string(),
+        Outcome::Failed(error) => error.to_string(),
+    }
+}
+
+/// Search the solutions on a single thread, checking each of them with --verify, and only the symmetric ones with --symmetric
+fn search<V: SolutionVisitor>(lattice: &Lattice, metrics: &mut Metrics, visitor: &mut V, options: &Options) -> Result<Count, Error> {
+    let mut board = initialize_board(lattice)?;
+    board.set_folding(options.fold);
+    board.set_verify(options.verify);
+    board.set_connectivity(options.prune);
+    if let Some(symmetry) = options.symmetry {
+        board.require_symmetry(symmetry)?;
+    }
+    Ok(count_board(&mut board, metrics, visitor))
+}
+
+/// Draw the solutions (or the puzzle alone) on one SVG sheet, if asked for
+fn write_svg(puzzle: &Puzzle, cycles: &[Cycle], options: &Options) -> Result<(), Error> {
+    let file = match &options.svg {
+        Some(file) => file,
+        None => return Ok(()),
+    };
+    let mut style = SvgStyle {
+        cell_size: options.cell_size as f64,
+        ..SvgStyle::default()
+    };
+    if options.shade {
+        style.fill_colour = Some("#dce6f5".to_string());
+    }
+    // as many boards to a row as there are rows
+    let mut columns = 1;
+    while columns * columns < cycles.len() {
+        columns += 1;
+    }
+    fs::write(file, svg_sheet(puzzle, cycles, columns, &style)).map_err(|e| io_error(file, e))
+}
+
+fn io_error(path: &Path, error: io::Error) -> Error {
+    Error::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+/// Write listed solutions to the output file, or print them unless only the count was asked for
+fn write_output(text: &str, options: &Options) -> Result<(), Error> {
+    match &options.output {
+        Some(output) => fs::write(output, text).map_err(|e| io_error(output, e)),
+        None if options.format == Format::Text => {
+            print!("{}", text);
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
 
+fn run_resume(checkpoint: &Path, options: &Options) -> Result<(), Error> {
+    let mut metrics = Metrics::new();
+    let (lattice, count) = resume_from_checkpoint(checkpoint, &mut metrics, options.threads, options.checkpoint_interval)?;
+    if options.format == Format::Text && options.verbosity > Verbosity::Quiet {
+        println!("Resumed search for {:?} x {:?} matrix", lattice.n(), lattice.m());
+    }
+    print_report(lattice.n(), lattice.m(), Algorithm::Search, count, None, &metrics, options)
+}
+
+fn run_solve(file: &Path, options: &Options) -> Result<(), Error> {
+    let text = fs::read_to_string(file).map_err(|e| io_error(file, e))?;
+    let puzzle = parse_puzzle(&text).map_err(|e| match e {
+        Error::Parse { .. } => Error::Io {
+            path: file.display().to_string(),
+            message: e.to_string(),
+        },
+        e => e,
+    })?;
+    if options.format == Format::Text && options.verbosity > Verbosity::Quiet {
+        println!(
+            "Solving {:?} x {:?} puzzle with {} given and {} forbidden edges",
+            puzzle.n(),
+            puzzle.m(),
+            puzzle.given_edges().len(),
+            puzzle.forbidden_edges().len()
+        );
+        if options.draw {
+            println!("{}", render_puzzle(&puzzle));
+        }
+    }
+    let mut metrics = Metrics::new();
+    let solutions = puzzle.solve(options.max_solutions, &mut metrics)?;
+    print_report(puzzle.n(), puzzle.m(), Algorithm::Search, solutions.count, None, &metrics, options)?;
+    let mut grids = String::new();
+    for (k, cycle) in solutions.cycles.iter().enumerate() {
+        let grid = if options.draw {
+            render_cycle(puzzle.n(), puzzle.m(), cycle, options.shade)
+        } else {
+            write_solution(&puzzle, cycle)
+        };
+        grids += &format!("# solution {}\n{}", k + 1, grid);
+    }
+    write_svg(&puzzle, &solutions.cycles, options)?;
+    write_output(&grids, options)
+}
+
+fn print_report(
+    n: usize,
+    m: usize,
+    algorithm: Algorithm,
+    count: Count,
+    symmetric: Option<&CountSymmetric>,
+    metrics: &Metrics,
+    options: &Options,
+) -> Result<(), Error> {
+    let report = Report {
+        n,
+        m,
+        algorithm,
+        count,
+        classes: symmetric.map(|symmetric| symmetric.classes()),
+        metrics: metrics.clone(),
+        wall_time: metrics.run_duration.elapsed().unwrap_or_default(),
+    };
+    // written through a handle, so a closed pipe (such as `| head -1`) ends in an error instead of a panic
+    let mut out = stdout().lock();
+    write_report(&mut out, &report, symmetric, options)
+        .and_then(|_| out.flush())
+        .map_err(|e| io_error(Path::new("stdout"), e))
+}
+
+fn write_report(out: &mut dyn Write, report: &Report, symmetric: Option<&CountSymmetric>, options: &Options) -> io::Result<()> {
+    match options.format {
+        Format::Count | Format::Csv => writeln!(out, "{}", report.count)?,
+        Format::Json => writeln!(out, "{}", report.to_json())?,
+        Format::Text if options.verbosity == Verbosity::Quiet => {
+            writeln!(out, "{} solutions found", report.count)?;
+            if let Some(symmetric) = symmetric {
+                writeln!(out, "{} different up to rotations and reflections", symmetric.classes())?;
             }
-            if check_i {
-                // traverse edge from v to i and search for solutions from there
-                metrics.visited_vertices += 1;
-                check_board(board, 
-                    rim_vertices, 
-                    // visited_rim_vertices, 
-                    // visited_vertices + 1, 
-                    solution_path, 
-                    // solutions_counter, 
-                    i, // next vertice to visit
-                    n, m, 
-                    metrics, 
-                    // check_counter, fail_counter_1, fail_counter_2, fail_counter_3, exception_counter, run_duration
-                ); 
-                metrics.visited_vertices -= 1;
+        }
+        Format::Text if report.algorithm != Algorithm::Search => {
+            writeln!(out)?;
+            writeln!(out, "{} solutions found", report.count)?;
+            writeln!(out, "Run duration: {:?}", report.metrics.run_duration.elapsed())?;
+        }
+        Format::Text => {
+            writeln!(out)?;
+            writeln!(out, "{} solutions found", report.count)?;
+            if let Some(symmetric) = symmetric {
+                writeln!(out, "{} different up to rotations and reflections", symmetric.classes())?;
+                for (symmetry, fixed) in &symmetric.fixed {
+                    writeln!(out, "Unchanged by the {} = {}", symmetry, fixed)?;
+                }
+            }
+            writeln!(out, "Check_counter = {}", report.metrics.check_counter)?;
+            writeln!(out, "Fail counter 1 = {}", report.metrics.fail_counter_1)?;
+            writeln!(out, "Fail counter 2 = {}", report.metrics.fail_counter_2)?;
+            writeln!(out, "Fail counter 3 = {}", report.metrics.fail_counter_3)?;
+            writeln!(out, "Exception counter = {}", report.metrics.exception_counter)?;
+            writeln!(out, "Nodes per second = {:.0}", report.metrics.nodes_per_second())?;
+            writeln!(out, "Run duration: {:?}", report.metrics.run_duration.elapsed())?;
+            if options.verify {
+                if report.metrics.fail_counter_1 == 0 && report.metrics.exception_counter == 0 {
+                    writeln!(out, "Verified: every path through all vertices closed the loop as a valid cycle")?;
+                } else {
+                    writeln!(
+                        out,
+                        "Verification FAILED: {} paths did not close the loop, {} were not valid cycles",
+                        report.metrics.fail_counter_1, report.metrics.exception_counter
+                    )?;
+                }
             }
         }
     }
-    board[v][v] = false; // mark vertice v as unvisited
-    solution_path.pop();
-    board[store_j][store_next_rim_vertice] = false; // reset if 'return edge' was set true 
-    // println!("backtrack - check_counter = {}", check_counter);
-    //print!("-{},", v);  // debug print
-    metrics.fail_counter_3 += 1;
-    return;
+    Ok(())
 }
 
-fn main() {
+fn interactive(options: &Options) {
     loop {
         println!("--- La Linea RoundTrip ---");
         let (n, m) = get_matrix_dimension();
         if n == 0 || m == 0 {
             break;
         }
-        if validate_board_size(n, m) == false {
+        if let Err(message) = run_count(n, m, options) {
+            println!("{}", message);
             println!("Adjust parameters and try again!");
-        } else {
-            println!("Initializing");
-            let mut board = [[false; N_MAX * M_MAX]; N_MAX * M_MAX];
-            let mut rim_vertices: Vec<usize> = vec![];
-            initialize_board(&mut board, &mut rim_vertices, n, m);
-            println!("Searching solutions for {:?} x {:?} matrix", n, m);
-            let mut metrics = Metrics::new();
-            // let run_duration = SystemTime::now();
-            // let mut check_counter: i64 = 0;
-            // let mut fail_counter_1: i64 = 0;
-            // let mut fail_counter_2: i64 = 0;
-            // let mut fail_counter_3: i64 = 0;
-            // let mut exception_counter: i64 = 0;
-            // let mut solutions_counter: i64 = 0;
-            // let visited_vertices = 0;
-            // let visited_rim_vertices = 0;
-            let vertice_to_visit = 0;   // start with vertice 0
-            let mut solution_path: Vec<usize> = vec![];
-            check_board(
-                &mut board,
-                &rim_vertices,
-                // visited_rim_vertices,
-                // visited_vertices,
-                &mut solution_path, 
-                // &mut solutions_counter, 
-                vertice_to_visit, 
-                n,
-                m,
-                &mut metrics,
-                // &mut check_counter,
-                // &mut fail_counter_1,
-                // &mut fail_counter_2,
-                // &mut fail_counter_3,
-                // &mut exception_counter,
-                // &run_duration,
-            );
-            println!("");
-            println!("{} solutions found", metrics.solutions_counter);
-            println!("Check_counter = {}", metrics.check_counter);
-            println!("Fail counter 1 = {}", metrics.fail_counter_1);
-            println!("Fail counter 2 = {}", metrics.fail_counter_2);
-            println!("Fail counter 3 = {}", metrics.fail_counter_3);
-            println!("Exception counter = {}", metrics.exception_counter);
-            println!("Run duration: {:?}", metrics.run_duration.elapsed());
         }
     }
 }
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, options) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("Try 'roundtrip --help' for more information.");
+            process::exit(EXIT_USAGE);
+        }
+    };
+    let exit_code = match command {
+        Command::Interactive => {
+            interactive(&options);
+            EXIT_SUCCESS
+        }
+        Command::Help => {
+            println!("{}", USAGE);
+            EXIT_SUCCESS
+        }
+        Command::Count { n, m } => match run_count(n, m, &options) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(message) => {
+                eprintln!("{}", message);
+                EXIT_FAILURE
+            }
+        },
+        Command::Sweep { n, m } => match run_sweep(n, m, &options) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(message) => {
+                eprintln!("{}", message);
+                EXIT_FAILURE
+            }
+        },
+        Command::CrossCheck { n, m } => match run_cross_check(n, m, &options) {
+            Ok(true) => EXIT_SUCCESS,
+            Ok(false) => EXIT_FAILURE,
+            Err(message) => {
+                eprintln!("{}", message);
+                EXIT_FAILURE
+            }
+        },
+        Command::Solve { puzzle } => match run_solve(&puzzle, &options) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(message) => {
+                eprintln!("{}", message);
+                EXIT_FAILURE
+            }
+        },
+        Command::Resume { checkpoint } => match run_resume(&checkpoint, &options) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(message) => {
+                eprintln!("{}", message);
+                EXIT_FAILURE
+            }
+        },
+    };
+    process::exit(exit_code);
+}
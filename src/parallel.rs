@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::error::Error;
+use crate::lattice::Lattice;
+use crate::metrics::Metrics;
+use crate::search::{check_position, collect_positions, SearchPosition};
+use crate::Count;
+
+/// A split depth that gives plenty of positions to share between the threads on most boards
+pub fn default_split_depth(lattice: &Lattice) -> usize {
+    lattice.size() / 4
+}
+
+/// Count all solutions of a lattice on several threads.
+///
+/// The search tree is cut where the path has `split_depth` vertices, and the subtrees below
+/// are handed out to the threads one by one. Each thread keeps its own board and metrics,
+/// and the metrics are added up at the end, so the totals are the same as for `count_with_metrics`.
+pub fn count_parallel(lattice: &Lattice, metrics: &mut Metrics, threads: usize, split_depth: usize) -> Result<Count, Error> {
+    let positions = collect_positions(lattice, split_depth, metrics)?;
+    check_positions(lattice, &positions, threads, metrics, |_, _| Ok(()))?;
+    Ok(Count::from(metrics.solutions_counter))
+}
+
+/// Continue the search from all the positions on several threads, adding their counters to `metrics`.
+///
+/// `position_done` is called on this thread with the index of each position as soon as it has been
+/// searched, together with the metrics added up so far.
+pub fn check_positions<F>(
+    lattice: &Lattice,
+    positions: &[SearchPosition],
+    threads: usize,
+    metrics: &mut Metrics,
+    mut position_done: F,
+) -> Result<(), Error>
+where
+    F: FnMut(usize, &Metrics) -> Result<(), Error>,
+{
+    let next_position = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let sender = sender.clone();
+            let next_position = &next_position;
+            scope.spawn(move || loop {
+                let k = next_position.fetch_add(1, Ordering::Relaxed);
+                if k >= positions.len() {
+                    return;
+                }
+                let mut position_metrics = Metrics::new();
+                let result = check_position(lattice, &positions[k], &mut position_metrics);
+                if sender.send((k, result.map(|_| position_metrics))).is_err() {
+                    return;
+                }
+            });
+        }
+        drop(sender);
+        for (k, result) in receiver {
+            metrics.merge(&result?);
+            position_done(k, metrics)?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,104 @@
+use crate::cycle::{Cycle, Solutions};
+use crate::error::Error;
+use crate::lattice::{validate_board_size, Lattice};
+use crate::metrics::Metrics;
+use crate::search::{count_board, initialize_board};
+use crate::visitor::CollectCycles;
+
+/// A board where some of the edges have already been drawn, like the puzzle in the newspaper,
+/// and some may have been ruled out.
+///
+/// The vertices are numbered as for a `Lattice` (vertex `j*n + i` is in column i of row j), but a puzzle
+/// may also be wider than it is high.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    n: usize,
+    m: usize,
+    given: Vec<(usize, usize)>,
+    forbidden: Vec<(usize, usize)>,
+}
+
+impl Puzzle {
+    /// An n x m puzzle without any edges (n and m in either order)
+    pub fn new(n: usize, m: usize) -> Result<Self, Error> {
+        validate_board_size(n.min(m), n.max(m))?;
+        Ok(Puzzle {
+            n,
+            m,
+            given: vec![],
+            forbidden: vec![],
+        })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    /// Draw the edge between the neighbour vertices a and b
+    pub fn add_given_edge(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        self.check_edge(a, b)?;
+        if !self.given.contains(&(a, b)) && !self.given.contains(&(b, a)) {
+            self.given.push((a, b));
+        }
+        Ok(())
+    }
+
+    /// Rule out the edge between the neighbour vertices a and b
+    pub fn add_forbidden_edge(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        self.check_edge(a, b)?;
+        if !self.forbidden.contains(&(a, b)) && !self.forbidden.contains(&(b, a)) {
+            self.forbidden.push((a, b));
+        }
+        Ok(())
+    }
+
+    pub fn given_edges(&self) -> &[(usize, usize)] {
+        &self.given
+    }
+
+    pub fn forbidden_edges(&self) -> &[(usize, usize)] {
+        &self.forbidden
+    }
+
+    fn check_edge(&self, a: usize, b: usize) -> Result<(), Error> {
+        let (low, high) = (a.min(b), a.max(b));
+        let neighbours = high - low == self.n || (high - low == 1 && !high.is_multiple_of(self.n));
+        if high >= self.n * self.m || !neighbours {
+            return Err(Error::NotAnEdge { a, b });
+        }
+        Ok(())
+    }
+
+    /// Find the completions of the puzzle, keeping the first `max_cycles` of them
+    pub fn solve(&self, max_cycles: usize, metrics: &mut Metrics) -> Result<Solutions, Error> {
+        // the search wants a 'high and thin' lattice, so a wide puzzle is solved on its transpose
+        let transposed = self.n > self.m;
+        let (n, m) = (self.n, self.m);
+        let to_lattice = |v: usize| if transposed { (v % n) * m + v / n } else { v };
+        let from_lattice = |v: usize| if transposed { (v % m) * n + v / m } else { v };
+        let lattice = if transposed { Lattice::new(m, n)? } else { Lattice::new(n, m)? };
+
+        let mut board = initialize_board(&lattice)?;
+        for &(a, b) in &self.given {
+            board.add_given_edge(to_lattice(a), to_lattice(b))?;
+        }
+        for &(a, b) in &self.forbidden {
+            board.forbid_edge(to_lattice(a), to_lattice(b))?;
+        }
+        let mut collect = CollectCycles::new(max_cycles);
+        let count = count_board(&mut board, metrics, &mut collect);
+        let cycles = collect
+            .cycles
+            .into_iter()
+            .map(|cycle| Cycle::new(cycle.vertices.into_iter().map(from_lattice).collect()))
+            .collect();
+        Ok(Solutions {
+            count,
+            cycles,
+        })
+    }
+}
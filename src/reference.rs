@@ -0,0 +1,91 @@
+/*-------------------------------------
+  Reference counter
+
+    check_board owes its speed to several tricks - the rim is only traveled clockwise, return edges are opened
+    from the interior to the rim one at a time, and the 'must go left' and island rules cut branches early.
+    Any of them could drop solutions without anyone noticing. The reference counter uses none of them: it follows
+    every path from vertice 0 through the neighbours not yet visited, and counts the paths visiting all vertices
+    that end next to vertice 0. Every loop is found twice, once in each direction, so the total is halved.
+    It is slow, but simple enough to be obviously right, and the search can be checked against it on small boards.
+-------------------------------------*/
+
+use crate::error::Error;
+use crate::lattice::Lattice;
+use crate::metrics::Metrics;
+use crate::search::count_with_metrics;
+use crate::sweep::Sweep;
+use crate::{Algorithm, Count};
+
+/// A board size where the search and the reference counter disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub n: usize,
+    pub m: usize,
+    pub search: Count,
+    pub reference: Count,
+}
+
+/// Count the loops of a lattice by trying every path from vertice 0, without any pruning
+pub fn count_reference(lattice: &Lattice) -> Result<Count, Error> {
+    let (n, m) = (lattice.n(), lattice.m());
+    let mut neighbours = vec![vec![]; n * m];
+    for (v, next) in neighbours.iter_mut().enumerate() {
+        let (i, j) = (v % n, v / n);
+        if j > 0 {
+            next.push(v - n);
+        }
+        if i > 0 {
+            next.push(v - 1);
+        }
+        if i + 1 < n {
+            next.push(v + 1);
+        }
+        if j + 1 < m {
+            next.push(v + n);
+        }
+    }
+    let mut visited = vec![false; n * m];
+    visited[0] = true;
+    let paths = count_paths(&neighbours, &mut visited, 0, 1);
+    Ok(Count::from(paths / 2))
+}
+
+/// The number of paths from v through all the unvisited vertices, ending next to vertice 0
+fn count_paths(neighbours: &[Vec<usize>], visited: &mut [bool], v: usize, path_length: usize) -> u128 {
+    if path_length == visited.len() {
+        return if neighbours[v].contains(&0) { 1 } else { 0 };
+    }
+    let mut paths = 0;
+    for &i in &neighbours[v] {
+        if !visited[i] {
+            visited[i] = true;
+            paths += count_paths(neighbours, visited, i, path_length + 1);
+            visited[i] = false;
+        }
+    }
+    paths
+}
+
+/// Count every valid board with n <= max_n and m <= max_m with both the search and the reference counter,
+/// calling `checked` with each size as it is done, and return the sizes where they disagree
+pub fn cross_check<F: FnMut(usize, usize)>(max_n: usize, max_m: usize, mut checked: F) -> Result<Vec<Mismatch>, Error> {
+    let sweep = Sweep {
+        n: 1..=max_n,
+        m: 1..=max_m,
+        algorithm: Algorithm::Search,
+        budget: None,
+        folding: true,
+        connectivity: true,
+    };
+    let mut mismatches = vec![];
+    for (n, m) in sweep.sizes() {
+        let lattice = Lattice::new(n, m)?;
+        let search = count_with_metrics(&lattice, &mut Metrics::new())?;
+        let reference = count_reference(&lattice)?;
+        if search != reference {
+            mismatches.push(Mismatch { n, m, search, reference });
+        }
+        checked(n, m);
+    }
+    Ok(mismatches)
+}
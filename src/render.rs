@@ -0,0 +1,129 @@
+/*-------------------------------------
+  Box drawing
+
+    Draws a board with the box drawing characters, one character for every dot and one between
+    every two dots, so a 3 x 4 solution with the inside shaded looks like this:
+        ┌───┐
+        │░░░│
+        │░┌─┘
+        │░│
+        │░└─┐
+        │░░░│
+        └───┘
+    The character of a dot depends on the edges it has (┌ ┐ └ ┘ ─ │, and · for a dot without edges),
+    so a partial path or a puzzle can be drawn as well as a complete loop.
+-------------------------------------*/
+
+use crate::cycle::Cycle;
+use crate::puzzle::Puzzle;
+
+const SHADE: char = '░';
+const FORBIDDEN: char = '×';
+
+/// The character for a dot with edges up, down, left and right as given
+fn dot(up: bool, down: bool, left: bool, right: bool) -> char {
+    match (up, down, left, right) {
+        (false, false, false, false) => '·',
+        (false, false, false, true) => '╶',
+        (false, false, true, false) => '╴',
+        (false, false, true, true) => '─',
+        (false, true, false, false) => '╷',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (false, true, true, true) => '┬',
+        (true, false, false, false) => '╵',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, false, true, true) => '┴',
+        (true, true, false, false) => '│',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (true, true, true, true) => '┼',
+    }
+}
+
+/// Draw a loop on an n x m board, shading the tiles inside it if `shade_inside` is set
+pub fn render_cycle(n: usize, m: usize, cycle: &Cycle, shade_inside: bool) -> String {
+    draw(n, m, |a, b| cycle.has_edge(a, b), |_, _| false, shade_inside)
+}
+
+/// Draw the given edges of a puzzle, with × on the forbidden edges
+pub fn render_puzzle(puzzle: &Puzzle) -> String {
+    let has = |edges: &[(usize, usize)], a: usize, b: usize| edges.contains(&(a, b)) || edges.contains(&(b, a));
+    draw(
+        puzzle.n(),
+        puzzle.m(),
+        |a, b| has(puzzle.given_edges(), a, b),
+        |a, b| has(puzzle.forbidden_edges(), a, b),
+        false,
+    )
+}
+
+/// Draw a path that has not been closed yet, such as the `solution_path` of a search in progress
+pub fn render_path(n: usize, m: usize, solution_path: &[usize]) -> String {
+    let on_path = |a: usize, b: usize| solution_path.windows(2).any(|step| step == [a, b] || step == [b, a]);
+    draw(n, m, on_path, |_, _| false, false)
+}
+
+fn draw<F, G>(n: usize, m: usize, drawn: F, forbidden: G, shade_inside: bool) -> String
+where
+    F: Fn(usize, usize) -> bool,
+    G: Fn(usize, usize) -> bool,
+{
+    // a tile is inside the loop if an odd number of loop edges are to the left of it
+    let mut inside = vec![vec![false; n - 1]; m - 1];
+    for (j, row) in inside.iter_mut().enumerate() {
+        let mut crossings = false;
+        for (i, tile) in row.iter_mut().enumerate() {
+            crossings ^= drawn(j * n + i, (j + 1) * n + i);
+            *tile = shade_inside && crossings;
+        }
+    }
+    let shaded = |j: usize, i: usize| j + 1 < m && i + 1 < n && inside[j][i];
+    let mut text = String::new();
+    for j in 0..m {
+        let mut dots = String::new();
+        for i in 0..n {
+            let v = j * n + i;
+            let up = j > 0 && drawn(v - n, v);
+            let down = j + 1 < m && drawn(v, v + n);
+            let left = i > 0 && drawn(v - 1, v);
+            let right = i + 1 < n && drawn(v, v + 1);
+            dots.push(dot(up, down, left, right));
+            if i + 1 < n {
+                dots.push(if right {
+                    '─'
+                } else if forbidden(v, v + 1) {
+                    FORBIDDEN
+                } else if j > 0 && shaded(j, i) {
+                    SHADE
+                } else {
+                    ' '
+                });
+            }
+        }
+        text += dots.trim_end();
+        text.push('\n');
+        if j + 1 < m {
+            let mut edges = String::new();
+            for i in 0..n {
+                let v = j * n + i;
+                edges.push(if drawn(v, v + n) {
+                    '│'
+                } else if forbidden(v, v + n) {
+                    FORBIDDEN
+                } else if i > 0 && shaded(j, i) {
+                    SHADE
+                } else {
+                    ' '
+                });
+                if i + 1 < n {
+                    edges.push(if shaded(j, i) { SHADE } else { ' ' });
+                }
+            }
+            text += edges.trim_end();
+            text.push('\n');
+        }
+    }
+    text
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use crate::metrics::Metrics;
+use crate::{Algorithm, Count};
+
+/// The result of a run: what was counted, how, and the counters of the search
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub n: usize,
+    pub m: usize,
+    pub algorithm: Algorithm,
+    pub count: Count,
+    /// the number of solutions up to rotations and reflections, if they were counted
+    pub classes: Option<Count>,
+    pub metrics: Metrics,
+    pub wall_time: Duration,
+}
+
+impl Report {
+    /// The report as a JSON object on a single line, such as
+    /// `{"n":4,"m":4,"count":6,"algorithm":"search",...,"version":"0.1.0"}`.
+    /// Only the search keeps the counters, so with the other algorithms they are all null.
+    pub fn to_json(&self) -> String {
+        let metrics = &self.metrics;
+        let counter = |value: String| if self.algorithm == Algorithm::Search { value } else { "null".to_string() };
+        let fields = [
+            ("n", self.n.to_string()),
+            ("m", self.m.to_string()),
+            ("count", self.count.to_string()),
+            ("classes", self.classes.as_ref().map_or("null".to_string(), |classes| classes.to_string())),
+            ("algorithm", format!("\"{}\"", self.algorithm.name())),
+            ("wall_time_seconds", format!("{:.6}", self.wall_time.as_secs_f64())),
+            ("check_counter", counter(metrics.check_counter.to_string())),
+            ("fail_counter_1", counter(metrics.fail_counter_1.to_string())),
+            ("fail_counter_2", counter(metrics.fail_counter_2.to_string())),
+            ("fail_counter_3", counter(metrics.fail_counter_3.to_string())),
+            ("exception_counter", counter(metrics.exception_counter.to_string())),
+            ("solutions_counter", counter(metrics.solutions_counter.to_string())),
+            ("visited_vertices", counter(metrics.visited_vertices.to_string())),
+            ("visited_rim_vertices", counter(metrics.visited_rim_vertices.to_string())),
+            ("nodes_per_second", counter(format!("{:.0}", metrics.nodes_per_second()))),
+            ("version", format!("\"{}\"", env!("CARGO_PKG_VERSION"))),
+        ];
+        let fields: Vec<String> = fields.iter().map(|(key, value)| format!("\"{}\":{}", key, value)).collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
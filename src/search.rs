@@ -0,0 +1,770 @@
+use crate::bitset::{VertexSet, WideSet};
+use crate::cycle::{Cycle, Solutions};
+use crate::error::Error;
+use crate::lattice::Lattice;
+use crate::metrics::Metrics;
+use crate::symmetry::{edge_bits, symmetry_allows, Symmetry};
+use crate::visitor::{CollectCycles, CountSolutions, SolutionVisitor, Visit};
+use crate::Count;
+
+/// A board to search: a lattice, the edges given or forbidden on it, and the options of the search
+pub struct Board {
+    state: States,
+}
+
+/// The search state of a board, in the sets that fit its size
+enum States {
+    Narrow(SearchState<u128>),
+    Wide(SearchState<WideSet>),
+}
+
+/// Run `$body` on the search state of the board, whichever sets it keeps
+macro_rules! on_state {
+    ($board:expr, $state:ident => $body:expr) => {
+        match &mut $board.state {
+            States::Narrow($state) => $body,
+            States::Wide($state) => $body,
+        }
+    };
+}
+
+/// The search state of a lattice: the directed edges the path may use, and the visited vertices, as sets of vertices
+pub(crate) struct SearchState<S: VertexSet> {
+    pub(crate) lattice: Lattice,
+    /// edges[v] has bit i set if there is an edge from vertice v to vertice i
+    pub(crate) edges: Vec<S>,
+    /// neighbours[v] lists the vertices next to v in the lattice (regardless of the direction of the edges)
+    pub(crate) neighbours: Vec<Vec<usize>>,
+    /// the vertices on the path so far
+    pub(crate) visited: S,
+    /// the rim vertices as a bitset ...
+    pub(crate) rim: S,
+    /// ... and in clockwise order
+    pub(crate) rim_vertices: Vec<usize>,
+    /// the return edges (from, to) opened from the interior to the rim along the path so far
+    pub(crate) return_edges: Vec<(usize, usize)>,
+    /// when set, the search stops when the path reaches this length and records the position in `positions` instead
+    pub(crate) split_depth: Option<usize>,
+    pub(crate) positions: Vec<SearchPosition>,
+    /// given[v] holds the vertices v must be connected to (edges drawn in a puzzle)
+    pub(crate) given: Vec<S>,
+    /// set once an edge is given or forbidden, which leaves the board without its symmetries
+    pub(crate) puzzle: bool,
+    /// when set, every path through all vertices is checked to close the loop and to be a valid cycle
+    pub(crate) verify: bool,
+    /// when set, only loops left unchanged by this symmetry are searched for
+    pub(crate) symmetry: Option<Symmetry>,
+    /// when set, `count_board` folds the rotated and mirrored copies of the loops if it can (see `Board::set_folding`)
+    pub(crate) fold: bool,
+    /// when set, only one loop of every class of rotated and mirrored copies is searched for, and counted for the
+    /// whole class (see `fold_allows` and `solution_weight`) - for counting only, as the visitor does not see the copies
+    pub(crate) folding: bool,
+    /// the symmetries of the board other than the identity, each with the rim edges it takes the rim edges from
+    /// (see `rim_edge_images`)
+    pub(crate) folds: Vec<(Symmetry, Vec<usize>)>,
+    /// fold_from[p][k]: once the path has left rim vertice p, the loop and its copy under folds[k] have the same rim edges
+    /// before this one (or `usize::MAX` when the loop is known to come out larger) - so the comparison can go on from there
+    pub(crate) fold_from: Vec<Vec<usize>>,
+    /// place[v] is the index of vertice v on the path, while v is visited (kept up to date when there is a symmetry or folding)
+    pub(crate) place: Vec<usize>,
+    /// when set, the path only goes on to a vertice if all unvisited vertices can still be reached from there
+    /// (see `unvisited_connected`)
+    pub(crate) connectivity: bool,
+    /// the vertices in the left and the right column (to keep the flood fill in `unvisited_connected` from wrapping around the rows)
+    pub(crate) left_column: S,
+    pub(crate) right_column: S,
+}
+
+impl Board {
+    /// Require every solution to use the edge between a and b
+    pub fn add_given_edge(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        on_state!(self, state => {
+            if a >= state.lattice.size() || !state.neighbours[a].contains(&b) {
+                return Err(Error::NotAnEdge { a, b });
+            }
+            state.given[a].insert(b);
+            state.given[b].insert(a);
+            state.puzzle = true;
+        });
+        Ok(())
+    }
+
+    /// Only search for loops left unchanged by the symmetry
+    pub fn require_symmetry(&mut self, symmetry: Symmetry) -> Result<(), Error> {
+        on_state!(self, state => {
+            if !Symmetry::group(&state.lattice).contains(&symmetry) {
+                return Err(Error::NotSquare {
+                    n: state.lattice.n(),
+                    m: state.lattice.m(),
+                });
+            }
+            state.symmetry = Some(symmetry);
+        });
+        Ok(())
+    }
+
+    /// Remove the edge between a and b from the board (in both directions)
+    pub fn forbid_edge(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        on_state!(self, state => {
+            if a >= state.lattice.size() || !state.neighbours[a].contains(&b) {
+                return Err(Error::NotAnEdge { a, b });
+            }
+            // (a return edge is only opened when the edge from the rim vertice to the interior is still there)
+            state.edges[a].remove(b);
+            state.edges[b].remove(a);
+            state.puzzle = true;
+        });
+        Ok(())
+    }
+
+    /// Check every path through all vertices to close the loop and to be a valid cycle before it is counted
+    /// (see `verify_with_visitor`)
+    pub fn set_verify(&mut self, verify: bool) {
+        on_state!(self, state => state.verify = verify)
+    }
+
+    /// Give up a path as soon as it cuts the unvisited vertices apart (on by default)
+    pub fn set_connectivity(&mut self, connectivity: bool) {
+        on_state!(self, state => state.connectivity = connectivity)
+    }
+
+    /// Count the rotated and mirrored copies of a loop from one of them instead of searching them (on by default).
+    /// `count_board` only does so for a visitor that just counts (see `SolutionVisitor::counts_only`), on a board
+    /// without given or forbidden edges, a required symmetry or verify.
+    pub fn set_folding(&mut self, fold: bool) {
+        on_state!(self, state => state.fold = fold)
+    }
+}
+
+/// A path from vertice 0 and the vertice it is about to continue to; enough to resume the search from there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchPosition {
+    pub solution_path: Vec<usize>,
+    pub return_edges: Vec<(usize, usize)>,
+    pub next_vertice: usize,
+}
+
+pub fn initialize_board(lattice: &Lattice) -> Result<Board, Error> {
+    // (the search on a board that fits in u128 bitsets is several times faster)
+    let state = if lattice.size() <= u128::CAPACITY {
+        States::Narrow(initialize_state(lattice))
+    } else {
+        States::Wide(initialize_state(lattice))
+    };
+    Ok(Board { state })
+}
+
+pub(crate) fn initialize_state<S: VertexSet>(lattice: &Lattice) -> SearchState<S> {
+    let (n, m) = (lattice.n(), lattice.m());
+    // Initialize the edges: every vertice has an edge to each of its neighbours
+    // If there is an edge both from i to j and from j to i, the edge betweed i and j is undirected
+    let mut neighbours = vec![vec![]; n * m];
+    let mut edges = vec![S::empty(n * m); n * m];
+    for i in 0..n * m {
+        if i >= n {
+            neighbours[i].push(i - n);          // i has a neighbour above
+        }
+        if i % n != 0 {
+            neighbours[i].push(i - 1);          // i has a neighbour to the left (i is not at the left hand rim)
+        }
+        if (i + 1) % n != 0 {
+            neighbours[i].push(i + 1);          // i has a neighbour to the right (i is not at the right rim)
+        }
+        if i + n < n * m {
+            neighbours[i].push(i + n);          // i has a neighbour below
+        }
+        for &j in &neighbours[i] {
+            edges[i].insert(j);
+        }
+    }
+
+    // Now enforce some special initialization of directed edges for the rim nodes:
+    //      (1) Edges can only be traveled from a rim node to the inside of the lattice (initially)
+    //              (we reverse this operation for one edge at a time - whenever we travel from a rim node to the interior of the lattice
+    //              we allow the path to return to the rim through the (clockwise) neighbour edge)
+    //  AND (2) Nodes at the rim can only be visited in a clockwise direction 
+    //              (hence no anti-clockwise edges between rim nodes)
+    //  Note: (2) enforces that we only find paths going in clockwise direction. Otherwise we would find all paths twice; once 
+    //        going in a clockwise direction and once going anti-clockwise. 
+    for i in 0..n-1 {
+        edges[i+n+1].remove(i+1);                   // from second row to top (first) row
+        edges[n*(m-2)+i].remove(n*(m-1)+i);         // from second to last row to bottom (last) row
+        edges[i+1].remove(i);                       // top row
+        edges[n*(m-1)+i].remove(n*(m-1)+i+1);       // bottom row
+    }
+    for j in 0..m-1 {
+        edges[j*n+1].remove(j*n);                   // from second column to left rim (first) column
+        edges[(j+2)*n - 2].remove((j+2)*n - 1);     // from second to last column to right rim (last) column
+        edges[j*n].remove((j+1)*n);                 // left rim column
+        edges[(j+2)*n-1].remove((j+1)*n-1);         // right rim column
+    }
+
+    let rim_vertices = lattice.rim_vertices();
+    let folds: Vec<(Symmetry, Vec<usize>)> = Symmetry::group(lattice)
+        .into_iter()
+        .skip(1)
+        .map(|symmetry| (symmetry, rim_edge_images(lattice, &rim_vertices, symmetry)))
+        .collect();
+    let fold_from = vec![vec![0; folds.len()]; rim_vertices.len()];
+    let set_of = |vertices: &mut dyn Iterator<Item = usize>| {
+        let mut set = S::empty(n * m);
+        for v in vertices {
+            set.insert(v);
+        }
+        set
+    };
+    SearchState {
+        lattice: *lattice,
+        edges,
+        neighbours,
+        visited: S::empty(n * m),
+        rim: set_of(&mut rim_vertices.iter().copied()),
+        rim_vertices,
+        return_edges: vec![],
+        split_depth: None,
+        positions: vec![],
+        given: vec![S::empty(n * m); n * m],
+        puzzle: false,
+        verify: false,
+        symmetry: None,
+        fold: true,
+        folding: false,
+        folds,
+        fold_from,
+        place: vec![0; n * m],
+        connectivity: true,
+        left_column: set_of(&mut (0..m).map(|j| j * n)),
+        right_column: set_of(&mut (0..m).map(|j| j * n + n - 1)),
+    }
+}
+
+/// What happens when the path arrives in a vertice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Arrival {
+    /// the path can not be completed from here
+    DeadEnd,
+    /// the path visits all vertices and closes the loop (the vertice is not added to the path),
+    /// standing for this many loops (see `solution_weight`)
+    Solution(u128),
+    /// the vertice has been added to the path, and the search goes on from there
+    Continue,
+}
+
+/// Search for solutions continuing the path in `solution_path` through vertice v, handing them to the visitor
+/// (which counts them, if they are to be counted - see `CountSolutions`).
+///
+/// Returns `Visit::Stop` if the visitor stopped the search (the board is left as it was found either way).
+pub fn check_board<V: SolutionVisitor + ?Sized>(
+    board: &mut Board,
+    solution_path: &mut Vec<usize>,
+    v: usize,
+    metrics: &mut Metrics,
+    visitor: &mut V,
+) -> Visit {
+    on_state!(board, state => check_state(state, solution_path, v, metrics, visitor))
+}
+
+/// `check_board` on the search state of the board
+fn check_state<S: VertexSet, V: SolutionVisitor + ?Sized>(
+    board: &mut SearchState<S>,
+    solution_path: &mut Vec<usize>,
+    v: usize,
+    metrics: &mut Metrics,
+    visitor: &mut V,
+) -> Visit {
+    match arrive(board, solution_path, v, metrics) {
+        Arrival::DeadEnd => return Visit::Continue,
+        Arrival::Solution(weight) => {
+            solution_path.push(v);
+            let visit = visitor.solution(solution_path, weight, metrics);
+            solution_path.pop();
+            return visit;
+        }
+        Arrival::Continue => {}
+    }
+    let opened_return_edges = board.return_edges.len();
+    let mut visit = Visit::Continue;
+    let mut candidates = board.edges[v].and_not(&board.visited);
+    while let Some(i) = candidates.first() {
+        // there is an edge from v to i, and vertice i has not been visited yet
+        candidates.remove(i);
+        if may_visit(board, solution_path, i, metrics) {
+            // traverse edge from v to i and search for solutions from there
+            if board.split_depth == Some(solution_path.len()) {
+                // leave the rest of this branch to whoever picks up the position
+                board.positions.push(SearchPosition {
+                    solution_path: solution_path.clone(),
+                    return_edges: board.return_edges.clone(),
+                    next_vertice: i,
+                });
+                continue;
+            }
+            metrics.visited_vertices += 1;
+            visit = check_state(board, solution_path, i, metrics, visitor); // i is the next vertice to visit
+            metrics.visited_vertices -= 1;
+            if visit == Visit::Stop {
+                break;
+            }
+        }
+    }
+    if visit == Visit::Continue {
+        visit = visitor.backtrack(solution_path, metrics);
+    }
+    leave(board, solution_path, opened_return_edges, metrics);
+    visit
+}
+
+/// Let the path arrive in vertice v: count it, and see whether the path ends here
+pub(crate) fn arrive<S: VertexSet>(board: &mut SearchState<S>, solution_path: &mut Vec<usize>, v: usize, metrics: &mut Metrics) -> Arrival {
+    let size = board.lattice.size();
+    metrics.check_counter += 1;
+    // print!("{},", v);  // debug print
+    let at_the_rim = board.rim.contains(v);
+    if at_the_rim {
+        metrics.visited_rim_vertices += 1;
+        if metrics.visited_rim_vertices == board.rim_vertices.len() && metrics.visited_vertices + 1 < size {
+            metrics.fail_counter_2 += 1;
+            //println!("no more rim - backtrack, check_counter = {}", check_counter);  // debug print
+            //print!("-{},", v); // debug print
+            metrics.visited_rim_vertices -= 1;
+            return Arrival::DeadEnd;  // all rim vertices has been visited, but there remains unvisited interior vertices => fail!
+        } 
+    }
+    if metrics.visited_vertices + 1 == size {
+        //all vertices visited - can we make it back to the start vertice (0)?
+        // With board.verify this is checked first on the lattice itself, before the search's own edges are trusted
+        // (see verify_solution) - a path that does not close there is a bug in the search, not a dead end.
+        if board.verify && !verify_solution(board, solution_path, v, metrics) {
+            if at_the_rim {
+                metrics.visited_rim_vertices -= 1;
+            }
+            return Arrival::DeadEnd;
+        }
+        // The rim is visited clockwise, so v is vertice n, next to 0 - but the edge may have been ruled out in a puzzle.
+        if !board.edges[v].contains(0) {
+            metrics.fail_counter_1 += 1; // the edge closing the loop is forbidden (see Board::forbid_edge)
+            if at_the_rim {
+                metrics.visited_rim_vertices -= 1;
+            }
+            return Arrival::DeadEnd;
+        }
+        if !closes_given_edges(board, solution_path, v) {
+            metrics.fail_counter_1 += 1; // the loop misses some of the given edges
+            if at_the_rim {
+                metrics.visited_rim_vertices -= 1;
+            }
+            return Arrival::DeadEnd;
+        }
+        if let Some(symmetry) = board.symmetry {
+            let mut cycle = solution_path.clone();
+            cycle.push(v);
+            if !symmetry.fixes(&board.lattice, &cycle) {
+                metrics.fail_counter_1 += 1; // the edge closing the loop has no image
+                if at_the_rim {
+                    metrics.visited_rim_vertices -= 1;
+                }
+                return Arrival::DeadEnd;
+            }
+        }
+        // success!
+        // println!("... SOLUTION found!");
+        let weight = if board.folding { solution_weight(board, solution_path, v) } else { 1 };
+        if weight == 0 {
+            // a copy of the loop that stands for its class (not a failure: the loop is counted from that one)
+            if at_the_rim {
+                metrics.visited_rim_vertices -= 1;
+            }
+            return Arrival::DeadEnd;
+        }
+        //print!("-{}", v);
+        if at_the_rim {
+            metrics.visited_rim_vertices -= 1;
+        }
+        return Arrival::Solution(weight);
+    }
+
+    board.visited.insert(v); // mark vertice v as visited
+    if board.symmetry.is_some() || board.folding {
+        board.place[v] = solution_path.len();
+    }
+    solution_path.push(v);
+    Arrival::Continue
+}
+
+// Symmetry folding
+//
+// The rotated and mirrored copies of a loop are loops as well (4 copies on a rectangle, 8 on a square, fewer when the
+// loop is symmetric itself), and only one of them needs to be searched for. Write a loop as the string of its rim
+// edges, in the clockwise order the path decides them (rim edge p runs from rim vertice p to rim vertice p+1, and
+// the path decides it when it leaves rim vertice p), and let the loop with the largest string stand for its class.
+// The copy of a loop under a symmetry has rim edge p where the loop has the rim edge the symmetry moves onto p, so
+// as the path goes round the rim it compares the string of the loop with the string of every copy, and gives up as
+// soon as a copy is known to come out larger. Copies with the same string as the loop are told apart by all their
+// edges (`edge_bits`) once the loop is closed, and the loop is counted once for every distinct copy.
+// The mirror images (left to right) are mostly cut off along the top row, on a square board the anticlockwise quarter
+// turn and the anti-diagonal mirror along the right column, and the other copies only further round the rim.
+
+/// For every rim edge p: the rim edge the symmetry moves onto rim edge p
+/// (so the copy of a loop has rim edge p if the loop has the rim edge at images[p])
+fn rim_edge_images(lattice: &Lattice, rim_vertices: &[usize], symmetry: Symmetry) -> Vec<usize> {
+    let r = rim_vertices.len();
+    let mut rim_place = vec![usize::MAX; lattice.size()];
+    for (p, &v) in rim_vertices.iter().enumerate() {
+        rim_place[v] = p;
+    }
+    let inverse = symmetry.inverse();
+    (0..r)
+        .map(|p| {
+            let a = rim_place[inverse.map(lattice, rim_vertices[p])];
+            let b = rim_place[inverse.map(lattice, rim_vertices[(p + 1) % r])];
+            // the rim edge between a and b starts at whichever of them comes first going clockwise
+            if (a + 1) % r == b {
+                a
+            } else {
+                b
+            }
+        })
+        .collect()
+}
+
+/// Has the loop rim edge q, as far as the path has decided it? The path has left the rim vertices up to p, the
+/// last one towards `next`; the last rim edge (back to vertice 0) is always there.
+fn decided_rim_edge<S: VertexSet>(board: &SearchState<S>, q: usize, p: usize, next: usize) -> Option<bool> {
+    let r = board.rim_vertices.len();
+    if q == r - 1 {
+        Some(true)
+    } else if q < p {
+        let (a, b) = (board.rim_vertices[q], board.rim_vertices[q + 1]);
+        Some(board.visited.contains(b) && board.place[b] == board.place[a] + 1)
+    } else if q == p {
+        Some(next == board.rim_vertices[q + 1])
+    } else {
+        None
+    }
+}
+
+/// May the path leave rim vertice p for vertice `next`, or is a copy of the loop known to stand for its class instead?
+fn fold_allows<S: VertexSet>(board: &mut SearchState<S>, p: usize, next: usize) -> bool {
+    for k in 0..board.folds.len() {
+        // (a path resumed from a SearchPosition starts the comparisons over, from rim edge 0)
+        let mut q = if p > 0 { board.fold_from[p - 1][k] } else { 0 };
+        while q < board.rim_vertices.len() {
+            let image = board.folds[k].1[q];
+            match (decided_rim_edge(board, q, p, next), decided_rim_edge(board, image, p, next)) {
+                (Some(edge), Some(copy)) if edge == copy => q += 1,
+                (Some(false), Some(true)) => return false, // the copy comes out larger
+                (Some(_), Some(_)) => q = usize::MAX,      // the loop comes out larger
+                _ => break,                                // not decided yet
+            }
+        }
+        board.fold_from[p][k] = q;
+    }
+    true
+}
+
+/// How many loops the loop along the path (ending in v) stands for: the number of its distinct copies,
+/// or 0 if one of its copies stands for the class
+fn solution_weight<S: VertexSet>(board: &SearchState<S>, solution_path: &[usize], v: usize) -> u128 {
+    let lattice = &board.lattice;
+    let mut cycle = solution_path.to_vec();
+    cycle.push(v);
+    let mut next = vec![0; lattice.size()];
+    for (k, &w) in cycle.iter().enumerate() {
+        next[w] = cycle[(k + 1) % cycle.len()];
+    }
+    let r = board.rim_vertices.len();
+    let rim_edge = |q: usize| next[board.rim_vertices[q]] == board.rim_vertices[(q + 1) % r];
+    let mut bits = None;
+    let mut unchanged = 1; // (by the identity)
+    for (symmetry, images) in &board.folds {
+        match (0..r).map(|q| (rim_edge(q), rim_edge(images[q]))).find(|(edge, copy)| edge != copy) {
+            Some((false, true)) => return 0,
+            Some(_) => {}
+            None => {
+                let bits = bits.get_or_insert_with(|| edge_bits(lattice, &cycle));
+                let image: Vec<usize> = cycle.iter().map(|&w| symmetry.map(lattice, w)).collect();
+                let copy = edge_bits(lattice, &image);
+                if copy > *bits {
+                    return 0;
+                }
+                if copy == *bits {
+                    unchanged += 1;
+                }
+            }
+        }
+    }
+    // (every copy is made by as many symmetries as leave the loop unchanged)
+    (board.folds.len() as u128 + 1) / unchanged
+}
+
+/// Does the path through all vertices, ending in v, close the loop back to vertice 0, and is it a valid cycle?
+/// A path that does not close counts in fail_counter_1, a cycle that is not valid in exception_counter.
+fn verify_solution<S: VertexSet>(board: &SearchState<S>, solution_path: &[usize], v: usize, metrics: &mut Metrics) -> bool {
+    if !board.lattice.are_neighbours(v, 0) {
+        metrics.fail_counter_1 += 1;
+        return false;
+    }
+    let mut vertices = solution_path.to_vec();
+    vertices.push(v);
+    if !Cycle::new(vertices).is_valid_on(&board.lattice) {
+        metrics.exception_counter += 1;
+        return false;
+    }
+    true
+}
+
+/// May the path go on from its last vertice v to the unvisited neighbour i?
+/// (Going from the rim to the interior opens the return edge to the next rim vertice.)
+pub(crate) fn may_visit<S: VertexSet>(board: &mut SearchState<S>, solution_path: &[usize], i: usize, metrics: &Metrics) -> bool {
+    let n = board.lattice.n();
+    let v = solution_path[solution_path.len() - 1];
+    let at_the_rim = board.rim.contains(v);
+    let visited = &board.visited;
+    let mut check_i = true;
+    if at_the_rim {
+        if !board.rim.contains(i) { // we are about to enter the interior of the lattice...
+            check_i = false;            // ... but we don't want to go to the interior unless we can set a return path to the rim
+            // (this will happen when we are next to a corner vertice, or if we already visited the vertice providing the return edge)
+            let next_rim_vertice = board.rim_vertices[metrics.visited_rim_vertices];
+            let mut openings = board.edges[next_rim_vertice].and_not(&board.rim).and_not(visited);
+            while let Some(j) = openings.first() {
+                openings.remove(j);
+                board.edges[j].insert(next_rim_vertice);  // we 'open' the return edge from the interior to the next rim vertice
+                board.return_edges.push((j, next_rim_vertice));
+                check_i = true;  // return edge found - ok to continue 
+            }
+        }
+    } else if !board.rim.contains(i) {  // if we stay interior to the lattice...
+        //... then we can abort if we are about to create two separate 'islands' of unvisited vertices.
+        //  I.e. if we have unvisited vertices both left and right, while at least one of the vertices in front has been visited already.
+        //  This logic can be extended to incorporate cases when two regions are only connected through a single track
+        //  If so, we can only complete a cycle if we are in the opposite region to the one where our endpoint is.
+        //  In the special case where we approach the rim, the rim will act as such a single track connection and we have to go
+        //  to the left since the endpoint will always be to the right.
+        //  (The general case - the path cutting the unvisited vertices apart anywhere - is caught by unvisited_connected.)
+        if v>i && v-i == n {
+            // direction = 'n';
+            if !visited.contains(v+n) && board.rim.contains(v-1) && !visited.contains(v-1) {
+                check_i = false;
+            }   // "must go left" => cannot go this way
+            if (visited.contains(i-n) || visited.contains(i-n-1) || visited.contains(i-n+1)) &&
+                (!visited.contains(i-1) && !visited.contains(i+1)) {
+                    check_i = false;
+            }
+        } else if v<i && i-v == n {
+            // direction = 's';
+            if !visited.contains(v-n) && board.rim.contains(v+1) && !visited.contains(v+1) {
+                check_i = false;
+                //println!("south - must go left=north");
+                //println!("v={}, i={}, contains(visited, v-n)={}, {}, solution_path:", v, i, contains(visited, v-n), contains(board.rim, v+1));
+                //for k in 0..solution_path.len() {
+                //    print!("{},", solution_path[k]);
+                //}
+                //println!("");
+            }   // "must go left" => cannot go this way
+            if (visited.contains(i+n) || visited.contains(i+n-1) || visited.contains(i+n+1)) &&
+                (!visited.contains(i-1) && !visited.contains(i+1)) {
+                    check_i = false;
+            } 
+        } else if v>i && v-i == 1 {
+            // direction = 'w';
+            if !visited.contains(v+1) && board.rim.contains(v+n) && !visited.contains(v+n) {
+                check_i = false;
+            }   // "must go left" => cannot go this way
+            if (visited.contains(i-1) || visited.contains(i-1+n) || visited.contains(i-1-n)) &&
+                (!visited.contains(i+n) && !visited.contains(i-n)) {
+                    check_i = false;
+            }
+        } else if v<i && i-v == 1 {
+            // direction = 'e';
+            // If direction is 'east' we will always be going 'left'
+            if (visited.contains(i+1) || visited.contains(i+1+n) || visited.contains(i+1-n)) &&
+                (!visited.contains(i+n) && !visited.contains(i-n)) {
+                    check_i = false;
+            }
+        }
+
+    }
+    if check_i && !(board.given[v].is_empty() && board.given[i].is_empty()) {
+        check_i = given_edges_allow(board, solution_path, v, i);
+    }
+    if check_i && board.folding && at_the_rim {
+        // is the loop a rotated or mirrored copy of one that stands for its class?
+        check_i = fold_allows(board, metrics.visited_rim_vertices - 1, i);
+    }
+    if let Some(symmetry) = board.symmetry {
+        check_i = check_i && symmetry_allows(board, solution_path, v, i, symmetry);
+    }
+    if check_i && board.connectivity {
+        check_i = unvisited_connected(board, v, i);
+    }
+    check_i
+}
+
+// Connectivity pruning
+//
+// Once the path goes on to i, the rest of the loop runs from i through every unvisited vertice and back to vertice 0
+// (through vertice n, the last rim vertice, which stays unvisited until the end). So the unvisited vertices and i
+// have to hang together: if the path cuts them into two regions, the one i is not in can never be visited and the
+// branch can be given up right away - however far from the path the cut is, and however many moves it would take
+// the island check above to notice it.
+// Every move is checked, so before the move the unvisited vertices hung together with v: the move can only cut them
+// apart when v has other unvisited neighbours than i, and only then is the flood fill needed.
+
+/// Can every unvisited vertice still be reached from i (next to v) through unvisited vertices?
+/// (A flood fill over the bitsets, one step in each direction at a time.)
+fn unvisited_connected<S: VertexSet>(board: &SearchState<S>, v: usize, i: usize) -> bool {
+    if board.neighbours[v].iter().all(|&w| w == i || board.visited.contains(w)) {
+        return true;
+    }
+    let size = board.lattice.size();
+    let unvisited = S::full(size).and_not(&board.visited); // i included
+    let mut reached = S::empty(size);
+    reached.insert(i);
+    loop {
+        let next = reached.grow(board.lattice.n(), &board.left_column, &board.right_column, &unvisited);
+        if next == reached {
+            return reached == unvisited;
+        }
+        reached = next;
+    }
+}
+
+/// Take the last vertice off the path once every way to go on from it has been tried,
+/// closing the return edges opened since the path arrived there
+pub(crate) fn leave<S: VertexSet>(board: &mut SearchState<S>, solution_path: &mut Vec<usize>, opened_return_edges: usize, metrics: &mut Metrics) {
+    let v = solution_path.pop().unwrap();
+    board.visited.remove(v); // mark vertice v as unvisited
+    while board.return_edges.len() > opened_return_edges {
+        let (j, next_rim_vertice) = board.return_edges.pop().unwrap();
+        board.edges[j].remove(next_rim_vertice); // reset the 'return edge'
+    }
+    // println!("backtrack - check_counter = {}", check_counter);
+    //print!("-{},", v);  // debug print
+    metrics.fail_counter_3 += 1;
+    if board.rim.contains(v) {
+        metrics.visited_rim_vertices -= 1; // v is no longer part of the path
+    }
+}
+
+/// Can the path go on from v to i and still use all the given edges?
+/// v gets the edges from the previous vertice on the path and to i, and the given edges of i
+/// must leave room for the edge from v.
+fn given_edges_allow<S: VertexSet>(board: &SearchState<S>, solution_path: &[usize], v: usize, i: usize) -> bool {
+    if solution_path.len() >= 2 {
+        let previous = solution_path[solution_path.len() - 2];
+        if !only(&board.given[v], &[previous, i]) {
+            return false;
+        }
+    }
+    // (vertice 0 gets its second edge when the loop is closed, so it is checked in closes_given_edges)
+    let mut others = board.given[i].clone();
+    others.remove(v);
+    let mut complete = others.and(&board.visited);
+    complete.remove(0);
+    others.len() <= 1 && complete.is_empty()
+}
+
+/// Does closing the loop from the last vertice v back to vertice 0 use all the given edges of v and 0?
+fn closes_given_edges<S: VertexSet>(board: &SearchState<S>, solution_path: &[usize], v: usize) -> bool {
+    let previous = solution_path[solution_path.len() - 1];
+    only(&board.given[v], &[previous, 0]) && only(&board.given[0], &[solution_path[1], v])
+}
+
+/// Are all the vertices in the set among the allowed ones?
+fn only<S: VertexSet>(set: &S, allowed: &[usize]) -> bool {
+    let mut rest = set.clone();
+    for &k in allowed {
+        rest.remove(k);
+    }
+    rest.is_empty()
+}
+
+/// Count all solutions of a lattice, starting the search in vertice 0 (and counting the rotated and mirrored copies of a loop from one of them)
+pub fn count_with_metrics(lattice: &Lattice, metrics: &mut Metrics) -> Result<Count, Error> {
+    let mut board = initialize_board(lattice)?;
+    Ok(count_board(&mut board, metrics, &mut CountSolutions::default()))
+}
+
+/// Search all solutions of a lattice, handing each of them to the visitor, until the visitor stops the search.
+///
+/// Returns the number of solutions found.
+pub fn count_with_visitor<V: SolutionVisitor + ?Sized>(lattice: &Lattice, metrics: &mut Metrics, visitor: &mut V) -> Result<Count, Error> {
+    let mut board = initialize_board(lattice)?;
+    Ok(count_board(&mut board, metrics, visitor))
+}
+
+/// Search all solutions of a board prepared with `initialize_board` (and perhaps given edges or a symmetry),
+/// handing each of them to the visitor, until the visitor stops the search.
+///
+/// Returns the number of solutions found, which is added to `metrics.solutions_counter` as well.
+pub fn count_board<V: SolutionVisitor + ?Sized>(board: &mut Board, metrics: &mut Metrics, visitor: &mut V) -> Count {
+    let mut counter = CountSolutions::default();
+    on_state!(board, state => {
+        // (a visitor that sees the solutions would miss the copies, and on a puzzle the copies are not solutions)
+        state.folding = state.fold && visitor.counts_only() && !state.puzzle && state.symmetry.is_none() && !state.verify;
+        check_state(state, &mut vec![], 0, metrics, &mut (&mut counter, &mut *visitor));
+        state.folding = false;
+    });
+    metrics.solutions_counter += counter.solutions;
+    Count::from(counter.solutions)
+}
+
+/// Search all solutions like `count_with_visitor`, but check every path through all vertices before counting it:
+/// it must close the loop back to vertice 0 (fail_counter_1 counts the ones that don't) and be a valid cycle
+/// (exception_counter counts the ones that aren't). Both counters stay at 0 if the search is right.
+pub fn verify_with_visitor<V: SolutionVisitor + ?Sized>(lattice: &Lattice, metrics: &mut Metrics, visitor: &mut V) -> Result<Count, Error> {
+    let mut board = initialize_board(lattice)?;
+    board.set_verify(true);
+    Ok(count_board(&mut board, metrics, visitor))
+}
+
+/// Count all solutions of a lattice and keep the first `max_cycles` of them
+pub fn find_cycles(lattice: &Lattice, max_cycles: usize, metrics: &mut Metrics) -> Result<Solutions, Error> {
+    let mut collect = CollectCycles::new(max_cycles);
+    let count = count_with_visitor(lattice, metrics, &mut collect)?;
+    Ok(Solutions {
+        count,
+        cycles: collect.cycles,
+    })
+}
+
+/// Run the search from vertice 0 until the path has `split_depth` vertices, and return the positions reached.
+///
+/// The metrics count the part of the search above the split depth; continuing from every position with
+/// `check_position` gives the same totals as `count_with_metrics`.
+pub fn collect_positions(lattice: &Lattice, split_depth: usize, metrics: &mut Metrics) -> Result<Vec<SearchPosition>, Error> {
+    let mut board = initialize_board(lattice)?;
+    let mut counter = CountSolutions::default();
+    let positions = on_state!(board, state => {
+        state.folding = true;
+        state.split_depth = Some(split_depth.max(1));
+        check_state(state, &mut vec![], 0, metrics, &mut counter);
+        std::mem::take(&mut state.positions)
+    });
+    metrics.solutions_counter += counter.solutions;
+    Ok(positions)
+}
+
+/// Continue the search from a position collected by `collect_positions`
+pub fn check_position(lattice: &Lattice, position: &SearchPosition, metrics: &mut Metrics) -> Result<(), Error> {
+    let mut board = initialize_board(lattice)?;
+    let mut counter = CountSolutions::default();
+    on_state!(board, state => {
+        state.folding = true;
+        for (k, &v) in position.solution_path.iter().enumerate() {
+            state.visited.insert(v);
+            state.place[v] = k;
+        }
+        for &(j, next_rim_vertice) in &position.return_edges {
+            state.edges[j].insert(next_rim_vertice);
+        }
+        state.return_edges = position.return_edges.clone();
+        metrics.visited_vertices = position.solution_path.len();
+        metrics.visited_rim_vertices = position.solution_path.iter().filter(|&&v| state.rim.contains(v)).count();
+        let mut solution_path = position.solution_path.clone();
+        check_state(state, &mut solution_path, position.next_vertice, metrics, &mut counter);
+    });
+    metrics.solutions_counter += counter.solutions;
+    Ok(())
+}
@@ -0,0 +1,137 @@
+/*-------------------------------------
+  SVG drawings
+
+    A sheet holds one board, or a grid of boards with one solution each. A board is drawn as
+     - the dots
+     - the loop, optionally filled on the inside
+     - the given (clue) edges of the puzzle on top of it, in their own colour and wider,
+       and the forbidden edges as small crosses
+    Vertice (i, j) is drawn at (margin + i*cell_size, margin + j*cell_size) within its board.
+-------------------------------------*/
+
+use std::fmt::Write;
+
+use crate::cycle::Cycle;
+use crate::puzzle::Puzzle;
+
+/// Sizes (in pixels) and colours of an SVG drawing
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgStyle {
+    /// distance between two neighbour dots
+    pub cell_size: f64,
+    pub dot_radius: f64,
+    pub line_width: f64,
+    pub dot_colour: String,
+    pub loop_colour: String,
+    pub clue_colour: String,
+    /// the colour of the tiles inside the loop, or None to leave them blank
+    pub fill_colour: Option<String>,
+    pub background: String,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        SvgStyle {
+            cell_size: 24.0,
+            dot_radius: 2.5,
+            line_width: 3.0,
+            dot_colour: "#000000".to_string(),
+            loop_colour: "#1f5fbf".to_string(),
+            clue_colour: "#000000".to_string(),
+            fill_colour: None,
+            background: "#ffffff".to_string(),
+        }
+    }
+}
+
+/// The puzzle on its own, without a solution
+pub fn svg_puzzle(puzzle: &Puzzle, style: &SvgStyle) -> String {
+    svg_sheet(puzzle, &[], 1, style)
+}
+
+/// A single solution of the puzzle (or of a puzzle without clues, for a plain board)
+pub fn svg_solution(puzzle: &Puzzle, cycle: &Cycle, style: &SvgStyle) -> String {
+    svg_sheet(puzzle, std::slice::from_ref(cycle), 1, style)
+}
+
+/// The solutions of a puzzle side by side, `columns` boards to a row (the puzzle alone if there are no solutions)
+pub fn svg_sheet(puzzle: &Puzzle, cycles: &[Cycle], columns: usize, style: &SvgStyle) -> String {
+    let (n, m) = (puzzle.n(), puzzle.m());
+    let margin = style.cell_size / 2.0;
+    let board_width = (n - 1) as f64 * style.cell_size + 2.0 * margin;
+    let board_height = (m - 1) as f64 * style.cell_size + 2.0 * margin;
+    let boards = cycles.len().max(1);
+    let columns = columns.clamp(1, boards);
+    let rows = boards.div_ceil(columns);
+    let (width, height) = (columns as f64 * board_width, rows as f64 * board_height);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    );
+    let _ = writeln!(svg, r#"<rect width="{}" height="{}" fill="{}"/>"#, width, height, style.background);
+    for k in 0..boards {
+        let left = (k % columns) as f64 * board_width + margin;
+        let top = (k / columns) as f64 * board_height + margin;
+        let point = |v: usize| (left + (v % n) as f64 * style.cell_size, top + (v / n) as f64 * style.cell_size);
+        let _ = writeln!(svg, "<g>");
+        if let Some(cycle) = cycles.get(k) {
+            let points: Vec<String> = cycle
+                .vertices
+                .iter()
+                .map(|&v| {
+                    let (x, y) = point(v);
+                    format!("{},{}", x, y)
+                })
+                .collect();
+            let _ = writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{}" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+                points.join(" "),
+                style.fill_colour.as_deref().unwrap_or("none"),
+                style.loop_colour,
+                style.line_width
+            );
+        }
+        for &(a, b) in puzzle.given_edges() {
+            let ((x1, y1), (x2, y2)) = (point(a), point(b));
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+                x1,
+                y1,
+                x2,
+                y2,
+                style.clue_colour,
+                2.0 * style.line_width
+            );
+        }
+        for &(a, b) in puzzle.forbidden_edges() {
+            let ((x1, y1), (x2, y2)) = (point(a), point(b));
+            let (x, y, d) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0, style.cell_size / 8.0);
+            let _ = writeln!(
+                svg,
+                r#"<path d="M{} {}L{} {}M{} {}L{} {}" stroke="{}" stroke-width="{}"/>"#,
+                x - d,
+                y - d,
+                x + d,
+                y + d,
+                x - d,
+                y + d,
+                x + d,
+                y - d,
+                style.clue_colour,
+                style.line_width / 2.0
+            );
+        }
+        for v in 0..n * m {
+            let (x, y) = point(v);
+            let _ = writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#, x, y, style.dot_radius, style.dot_colour);
+        }
+        let _ = writeln!(svg, "</g>");
+    }
+    svg += "</svg>\n";
+    svg
+}
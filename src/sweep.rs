@@ -0,0 +1,191 @@
+/*-------------------------------------
+  Sweeps over board sizes
+
+    To build tables of counts we run the counter for every n x m with n and m in given ranges.
+    Sizes that are not valid boards (n > m, n * m odd or less than 12) are left out of the table.
+    With a time budget, the search is stopped when a size takes longer than the budget, and the
+    larger boards with the same n are skipped - they would only take longer still.
+-------------------------------------*/
+
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use crate::error::Error;
+use crate::lattice::{validate_board_size, Lattice};
+use crate::metrics::Metrics;
+use crate::reference::count_reference;
+use crate::search::{count_board, initialize_board};
+use crate::transfer::count_transfer_matrix;
+use crate::visitor::StopAfter;
+use crate::{Algorithm, Count};
+
+/// The columns of a sweep table written as CSV
+pub const SWEEP_CSV_HEADER: &str = "n,m,status,count,wall_time_seconds,check_counter";
+
+/// A sweep of the counter over the boards with n and m in the given ranges
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    pub n: RangeInclusive<usize>,
+    pub m: RangeInclusive<usize>,
+    pub algorithm: Algorithm,
+    /// the longest time spent on a single board size, if any
+    pub budget: Option<Duration>,
+    /// whether the search counts the rotated and mirrored copies of a loop from one of them (see `Board::set_folding`)
+    pub folding: bool,
+    /// whether the search gives up a path that cuts the unvisited vertices apart (see `Board::set_connectivity`)
+    pub connectivity: bool,
+}
+
+/// What became of one board size in a sweep
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Counted(Count),
+    /// the search was stopped when it ran out of time
+    TimedOut,
+    /// a smaller board with the same n ran out of time, so this one was not tried
+    OverBudget,
+    /// the counter can not handle the board (too wide for the transfer matrix, or a count too large for a u128)
+    Failed(Error),
+}
+
+/// One line of a sweep table
+#[derive(Debug, Clone)]
+pub struct SweepRow {
+    pub n: usize,
+    pub m: usize,
+    pub outcome: Outcome,
+    pub wall_time: Duration,
+    pub metrics: Metrics,
+}
+
+impl Sweep {
+    /// The valid board sizes of the sweep, n by n and m by m
+    pub fn sizes(&self) -> Vec<(usize, usize)> {
+        let mut sizes = vec![];
+        for n in self.n.clone() {
+            for m in self.m.clone() {
+                if validate_board_size(n, m).is_ok() {
+                    sizes.push((n, m));
+                }
+            }
+        }
+        sizes
+    }
+
+    /// Count the solutions of every size, calling `row_done` with each line of the table as soon as it is known
+    pub fn run<F: FnMut(&SweepRow)>(&self, mut row_done: F) -> Vec<SweepRow> {
+        let mut rows = vec![];
+        let mut over_budget_from: Option<usize> = None; // the n that ran out of time
+        for (n, m) in self.sizes() {
+            let row = if over_budget_from == Some(n) {
+                SweepRow {
+                    n,
+                    m,
+                    outcome: Outcome::OverBudget,
+                    wall_time: Duration::default(),
+                    metrics: Metrics::new(),
+                }
+            } else {
+                self.count(n, m)
+            };
+            let exceeded = match self.budget {
+                Some(budget) => row.outcome == Outcome::TimedOut || row.wall_time > budget,
+                None => false,
+            };
+            if exceeded {
+                over_budget_from = Some(n);
+            }
+            row_done(&row);
+            rows.push(row);
+        }
+        rows
+    }
+
+    fn count(&self, n: usize, m: usize) -> SweepRow {
+        let start = Instant::now();
+        let mut metrics = Metrics::new();
+        let outcome = match self.count_lattice(n, m, &mut metrics) {
+            Ok(Some(count)) => Outcome::Counted(count),
+            Ok(None) => Outcome::TimedOut,
+            Err(error) => Outcome::Failed(error),
+        };
+        SweepRow {
+            n,
+            m,
+            outcome,
+            wall_time: start.elapsed(),
+            metrics,
+        }
+    }
+
+    /// The count, or None if the search ran out of time
+    fn count_lattice(&self, n: usize, m: usize, metrics: &mut Metrics) -> Result<Option<Count>, Error> {
+        let lattice = Lattice::new(n, m)?;
+        match self.algorithm {
+            // (the transfer matrix and the reference counter can not be interrupted, so it only skips the sizes after one that took too long)
+            Algorithm::TransferMatrix => count_transfer_matrix(&lattice).map(Some),
+            Algorithm::Reference => count_reference(&lattice).map(Some),
+            Algorithm::Search => {
+                let mut board = initialize_board(&lattice)?;
+                board.set_folding(self.folding);
+                board.set_connectivity(self.connectivity);
+                let mut stop = self.budget.map(StopAfter::new);
+                let count = count_board(&mut board, metrics, &mut stop);
+                Ok(if stop.is_some_and(|stop| stop.timed_out) { None } else { Some(count) })
+            }
+        }
+    }
+}
+
+impl Outcome {
+    /// The name of the outcome in the status column of a table
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Counted(_) => "counted",
+            Outcome::TimedOut => "timeout",
+            Outcome::OverBudget => "skipped",
+            Outcome::Failed(_) => "failed",
+        }
+    }
+}
+
+impl SweepRow {
+    /// The row as a line of CSV, with the columns of `SWEEP_CSV_HEADER` (the count is empty unless counted)
+    pub fn to_csv(&self) -> String {
+        let count = match &self.outcome {
+            Outcome::Counted(count) => count.to_string(),
+            _ => String::new(),
+        };
+        format!(
+            "{},{},{},{},{:.6},{}",
+            self.n,
+            self.m,
+            self.outcome.status(),
+            count,
+            self.wall_time.as_secs_f64(),
+            self.metrics.check_counter
+        )
+    }
+
+    /// The row as a JSON object on a single line, such as
+    /// `{"n":4,"m":6,"status":"counted","count":37,"wall_time_seconds":0.000105,"check_counter":391}`
+    pub fn to_json(&self) -> String {
+        let count = match &self.outcome {
+            Outcome::Counted(count) => count.to_string(),
+            _ => "null".to_string(),
+        };
+        let mut json = format!(
+            "{{\"n\":{},\"m\":{},\"status\":\"{}\",\"count\":{},\"wall_time_seconds\":{:.6},\"check_counter\":{}",
+            self.n,
+            self.m,
+            self.outcome.status(),
+            count,
+            self.wall_time.as_secs_f64(),
+            self.metrics.check_counter
+        );
+        if let Outcome::Failed(error) = &self.outcome {
+            json += &format!(",\"error\":\"{}\"", error.to_string().replace('\\', "\\\\").replace('"', "\\\""));
+        }
+        json + "}"
+    }
+}
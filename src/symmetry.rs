@@ -0,0 +1,270 @@
+/*-------------------------------------
+  Symmetries of the board
+
+    Should a solution and the same solution rotated or flipped count as different? The search says yes - it counts
+    labelled loops. To count them the other way we use Burnside's lemma: the number of distinct loops (classes of
+    loops that can be turned into each other by a symmetry of the board) is the average over all symmetries of the
+    number of loops the symmetry leaves unchanged. The identity leaves every loop unchanged, so its number is the
+    labelled count, and the others only need checking loop by loop as the search finds them.
+
+    An n x m board with n < m has 4 symmetries: the identity, the half turn, and the two mirrors.
+    A square board has 8: the quarter turns and the two diagonal mirrors as well.
+
+    To list one loop of every class we pick a canonical loop: write a loop as a string of bits, one for every
+    edge of the lattice in the order of their vertices (the edge to the right of v, then the edge below v), and
+    the canonical loop of a class is the one whose string is the smallest. A loop is canonical if none of its
+    images under the symmetries has a smaller string.
+
+    Loops left unchanged by one given symmetry are searched for directly, rather than picked out of all the
+    solutions: every edge the path takes must have its image in the loop as well. As long as the path stays away
+    from the images of the vertices it has visited it is free to go anywhere, but where it meets them every step
+    is forced, so the search only really explores the part of the board the symmetry does not map onto the rest.
+-------------------------------------*/
+
+use std::fmt;
+
+use crate::lattice::Lattice;
+use crate::metrics::Metrics;
+use crate::bitset::VertexSet;
+use crate::search::SearchState;
+use crate::visitor::{SolutionVisitor, Visit};
+use crate::Count;
+
+/// A symmetry of the board, mapping every dot to a dot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    Identity,
+    /// quarter turn clockwise (square boards only)
+    Rotate90,
+    /// half turn
+    Rotate180,
+    /// quarter turn anticlockwise (square boards only)
+    Rotate270,
+    /// column i to column n-1-i
+    MirrorLeftRight,
+    /// row j to row m-1-j
+    MirrorTopBottom,
+    /// column i, row j to column j, row i (square boards only)
+    MirrorDiagonal,
+    /// column i, row j to column n-1-j, row n-1-i (square boards only)
+    MirrorAntiDiagonal,
+}
+
+impl Symmetry {
+    /// The symmetries of the lattice: 8 for a square board, otherwise 4
+    pub fn group(lattice: &Lattice) -> Vec<Symmetry> {
+        let mut group = vec![Symmetry::Identity, Symmetry::Rotate180, Symmetry::MirrorLeftRight, Symmetry::MirrorTopBottom];
+        if lattice.n() == lattice.m() {
+            group.extend([Symmetry::Rotate90, Symmetry::Rotate270, Symmetry::MirrorDiagonal, Symmetry::MirrorAntiDiagonal]);
+        }
+        group
+    }
+
+    /// The dot vertex v is moved to
+    pub fn map(&self, lattice: &Lattice, v: usize) -> usize {
+        let (n, m) = (lattice.n(), lattice.m());
+        let (i, j) = (v % n, v / n);
+        // (the quarter turns and diagonal mirrors are only symmetries when n == m)
+        let (i, j) = match self {
+            Symmetry::Identity => (i, j),
+            Symmetry::Rotate90 => (n - 1 - j, i),
+            Symmetry::Rotate180 => (n - 1 - i, m - 1 - j),
+            Symmetry::Rotate270 => (j, n - 1 - i),
+            Symmetry::MirrorLeftRight => (n - 1 - i, j),
+            Symmetry::MirrorTopBottom => (i, m - 1 - j),
+            Symmetry::MirrorDiagonal => (j, i),
+            Symmetry::MirrorAntiDiagonal => (n - 1 - j, n - 1 - i),
+        };
+        j * n + i
+    }
+
+    /// Every symmetry there is, for one board or another
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::MirrorLeftRight,
+        Symmetry::MirrorTopBottom,
+        Symmetry::MirrorDiagonal,
+        Symmetry::MirrorAntiDiagonal,
+    ];
+
+    /// The name used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::Identity => "identity",
+            Symmetry::Rotate90 => "quarter-turn",
+            Symmetry::Rotate180 => "half-turn",
+            Symmetry::Rotate270 => "three-quarter-turn",
+            Symmetry::MirrorLeftRight => "left-right",
+            Symmetry::MirrorTopBottom => "top-bottom",
+            Symmetry::MirrorDiagonal => "diagonal",
+            Symmetry::MirrorAntiDiagonal => "anti-diagonal",
+        }
+    }
+
+    /// The symmetry undoing this one
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            symmetry => *symmetry,
+        }
+    }
+
+    /// Does the symmetry leave the loop through `cycle` (the vertices in the order visited) unchanged?
+    pub fn fixes(&self, lattice: &Lattice, cycle: &[usize]) -> bool {
+        if *self == Symmetry::Identity {
+            return true;
+        }
+        // the loop is unchanged if the image of every edge is an edge of the loop:
+        // the images of two vertices next to each other on the loop are next to each other as well
+        let len = cycle.len();
+        let mut position = vec![0; lattice.size()];
+        for (k, &v) in cycle.iter().enumerate() {
+            position[v] = k;
+        }
+        (0..len).all(|k| {
+            let a = position[self.map(lattice, cycle[k])];
+            let b = position[self.map(lattice, cycle[(k + 1) % len])];
+            let distance = a.max(b) - a.min(b);
+            distance == 1 || distance == len - 1
+        })
+    }
+}
+
+/// Can the path go on from v to i and still become a loop left unchanged by the symmetry?
+/// The images of the new edge must not have been ruled out, and the edges v will not get (now that it gets
+/// its second edge) must not have images on the path.
+pub(crate) fn symmetry_allows<S: VertexSet>(board: &SearchState<S>, solution_path: &[usize], v: usize, i: usize, symmetry: Symmetry) -> bool {
+    let lattice = &board.lattice;
+    let previous = if solution_path.len() >= 2 { Some(solution_path[solution_path.len() - 2]) } else { None };
+    // is the edge between a and b on the path (or the edge from v to i about to be added)?
+    let on_path = |a: usize, b: usize| {
+        (a, b) == (v, i)
+            || (a, b) == (i, v)
+            || board.visited.contains(a) && board.visited.contains(b) && (board.place[a] as isize - board.place[b] as isize).abs() == 1
+    };
+    // has vertice w got both its edges, once v goes on to i? (vertice 0 gets its second edge when the loop is closed)
+    let complete = |w: usize| w != 0 && board.visited.contains(w);
+    for h in [symmetry, symmetry.inverse()] {
+        let (a, b) = (h.map(lattice, v), h.map(lattice, i));
+        if !on_path(a, b) && (complete(a) || complete(b)) {
+            return false;
+        }
+        if previous.is_some() {
+            for &x in &board.neighbours[v] {
+                if x != i && Some(x) != previous && on_path(a, h.map(lattice, x)) {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// The edges of the loop through `cycle` as bits, two for every vertex v: the edge to the right of v and the edge below v
+pub fn edge_bits(lattice: &Lattice, cycle: &[usize]) -> Vec<bool> {
+    let n = lattice.n();
+    let mut bits = vec![false; 2 * lattice.size()];
+    let next = cycle.iter().skip(1).chain(cycle.first());
+    for (&a, &b) in cycle.iter().zip(next) {
+        let (low, high) = (a.min(b), a.max(b));
+        bits[2 * low + if high - low == n { 1 } else { 0 }] = true;
+    }
+    bits
+}
+
+/// Is the loop through `cycle` the canonical loop of its class: is its `edge_bits` string at least as
+/// small as that of every rotated or flipped copy?
+pub fn is_canonical(lattice: &Lattice, cycle: &[usize]) -> bool {
+    let bits = edge_bits(lattice, cycle);
+    Symmetry::group(lattice).iter().skip(1).all(|symmetry| {
+        let image: Vec<usize> = cycle.iter().map(|&v| symmetry.map(lattice, v)).collect();
+        bits <= edge_bits(lattice, &image)
+    })
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Symmetry::Identity => "identity",
+            Symmetry::Rotate90 => "quarter turn clockwise",
+            Symmetry::Rotate180 => "half turn",
+            Symmetry::Rotate270 => "quarter turn anticlockwise",
+            Symmetry::MirrorLeftRight => "left-right mirror",
+            Symmetry::MirrorTopBottom => "top-bottom mirror",
+            Symmetry::MirrorDiagonal => "diagonal mirror",
+            Symmetry::MirrorAntiDiagonal => "anti-diagonal mirror",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Count the solutions left unchanged by each symmetry of the board, to count the distinct solutions by Burnside's lemma
+pub struct CountSymmetric {
+    pub lattice: Lattice,
+    /// every symmetry of the board with the number of solutions it leaves unchanged so far
+    pub fixed: Vec<(Symmetry, u128)>,
+}
+
+impl CountSymmetric {
+    pub fn new(lattice: &Lattice) -> Self {
+        CountSymmetric {
+            lattice: *lattice,
+            fixed: Symmetry::group(lattice).into_iter().map(|symmetry| (symmetry, 0)).collect(),
+        }
+    }
+
+    /// The number of solutions that are different even when rotated and flipped
+    /// (the average number of solutions left unchanged by a symmetry)
+    pub fn classes(&self) -> Count {
+        let total: u128 = self.fixed.iter().map(|(_, count)| count).sum();
+        Count::from(total / self.fixed.len() as u128)
+    }
+}
+
+/// Hand only the canonical solutions (one of every class of solutions that are rotated or flipped copies
+/// of each other) on to another visitor
+pub struct OnlyCanonical<V: SolutionVisitor> {
+    pub lattice: Lattice,
+    pub visitor: V,
+    /// the number of canonical solutions found so far
+    pub canonical: u128,
+}
+
+impl<V: SolutionVisitor> OnlyCanonical<V> {
+    pub fn new(lattice: &Lattice, visitor: V) -> Self {
+        OnlyCanonical {
+            lattice: *lattice,
+            visitor,
+            canonical: 0,
+        }
+    }
+}
+
+impl<V: SolutionVisitor> SolutionVisitor for OnlyCanonical<V> {
+    fn solution(&mut self, cycle: &[usize], weight: u128, metrics: &Metrics) -> Visit {
+        if !is_canonical(&self.lattice, cycle) {
+            return Visit::Continue;
+        }
+        self.canonical += 1;
+        self.visitor.solution(cycle, weight, metrics)
+    }
+
+    fn backtrack(&mut self, path: &[usize], metrics: &Metrics) -> Visit {
+        self.visitor.backtrack(path, metrics)
+    }
+}
+
+impl SolutionVisitor for CountSymmetric {
+    fn solution(&mut self, cycle: &[usize], _weight: u128, _metrics: &Metrics) -> Visit {
+        for (symmetry, count) in self.fixed.iter_mut() {
+            if symmetry.fixes(&self.lattice, cycle) {
+                *count += 1;
+            }
+        }
+        Visit::Continue
+    }
+}
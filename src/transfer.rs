@@ -0,0 +1,163 @@
+/*-------------------------------------
+  Transfer matrix counter
+
+    Instead of following the path vertice by vertice, we sweep the lattice row by row, one vertice at a time,
+    and keep track of how the edges crossing the boundary between visited and unvisited vertices (the 'frontier')
+    are connected. Two states with the same frontier have exactly the same completions, so we only need to
+    know how many partial solutions end up in each frontier state.
+
+    The frontier has n+1 positions. Before vertice (i, j) (column i, row j) is processed, positions 0..i hold the
+    edges going down from vertices (0..i, j), position i holds the edge coming from the left neighbour (i-1, j),
+    and positions i+1..=n hold the edges going down from vertices (i..n, j-1) in the row above.
+    Every edge crossing the frontier is the end of a path whose other end also crosses the frontier, and since
+    the paths cannot cross each other the pairs are nested like brackets. A position is therefore encoded as
+        0 - no edge
+        1 - the left end of a path  '('
+        2 - the right end of a path ')'
+    using 2 bits per position in a u64.
+
+    Every vertice must get exactly two edges. When a vertice is processed it has an edge from the left and from
+    above if the corresponding plugs are set, and it chooses its edges to the right and down accordingly:
+     - no edges in: start a new path going both right and down
+     - one edge in: continue the path either right or down
+     - two edges in: join the two paths (and if they are the two ends of the same path we close the loop,
+       which is only allowed at the very last vertice)
+-------------------------------------*/
+
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::lattice::Lattice;
+use crate::Count;
+
+/// The largest n the transfer matrix can handle (the n+1 frontier positions use 2 bits each in a u64)
+pub const MAX_FRONTIER_WIDTH: usize = 31;
+
+const NO_EDGE: u64 = 0;
+const OPEN: u64 = 1;
+const CLOSE: u64 = 2;
+
+fn plug(state: u64, k: usize) -> u64 {
+    (state >> (2 * k)) & 3
+}
+
+fn set_plug(state: u64, k: usize, value: u64) -> u64 {
+    (state & !(3 << (2 * k))) | (value << (2 * k))
+}
+
+fn add_state(states: &mut HashMap<u64, Count>, state: u64, count: &Count) -> Result<(), Error> {
+    add_count(states.entry(state).or_default(), count)
+}
+
+#[cfg(not(feature = "bigint"))]
+fn add_count(sum: &mut Count, count: &Count) -> Result<(), Error> {
+    *sum = sum.checked_add(*count).ok_or(Error::CountOverflow)?;
+    Ok(())
+}
+
+#[cfg(feature = "bigint")]
+fn add_count(sum: &mut Count, count: &Count) -> Result<(), Error> {
+    *sum += count;
+    Ok(())
+}
+
+/// Count all solutions of a lattice with a transfer matrix over the connectivity states of the frontier
+pub fn count_transfer_matrix(lattice: &Lattice) -> Result<Count, Error> {
+    let (n, m) = (lattice.n(), lattice.m());
+    if n > MAX_FRONTIER_WIDTH {
+        return Err(Error::TooWide { n, max: MAX_FRONTIER_WIDTH });
+    }
+    let mut states: HashMap<u64, Count> = HashMap::new();
+    states.insert(0, Count::from(1u8));
+    let mut solutions = Count::default();
+
+    for j in 0..m {
+        for i in 0..n {
+            let last_row = j == m - 1;
+            let last_column = i == n - 1;
+            let mut next_states = HashMap::with_capacity(states.len());
+            for (state, count) in states.drain() {
+                let left = plug(state, i);
+                let up = plug(state, i + 1);
+                let rest = set_plug(set_plug(state, i, NO_EDGE), i + 1, NO_EDGE);
+                match (left, up) {
+                    (NO_EDGE, NO_EDGE) => {
+                        if !last_row && !last_column {
+                            let state = set_plug(set_plug(rest, i, OPEN), i + 1, CLOSE);
+                            add_state(&mut next_states, state, &count)?;
+                        }
+                    }
+                    (end, NO_EDGE) | (NO_EDGE, end) => {
+                        if !last_row {
+                            add_state(&mut next_states, set_plug(rest, i, end), &count)?;
+                        }
+                        if !last_column {
+                            add_state(&mut next_states, set_plug(rest, i + 1, end), &count)?;
+                        }
+                    }
+                    (CLOSE, OPEN) => {
+                        // the right end of one path meets the left end of the next one - they become one path
+                        add_state(&mut next_states, rest, &count)?;
+                    }
+                    (OPEN, OPEN) => {
+                        // the path starting above continues from its right end; that end becomes the new left end
+                        let partner = matching_close(rest, i + 2, n);
+                        add_state(&mut next_states, set_plug(rest, partner, OPEN), &count)?;
+                    }
+                    (CLOSE, CLOSE) => {
+                        // the path coming from the left continues from its left end; that end becomes the new right end
+                        let partner = matching_open(rest, i);
+                        add_state(&mut next_states, set_plug(rest, partner, CLOSE), &count)?;
+                    }
+                    _ => {
+                        // (OPEN, CLOSE): the two ends of the same path meet and the loop is closed
+                        if last_row && last_column && rest == 0 {
+                            add_count(&mut solutions, &count)?;
+                        }
+                    }
+                }
+            }
+            states = next_states;
+        }
+        // move to the next row: the plug from the left of the first vertice is empty,
+        // and the edges going down from this row move one position to the right
+        states = states.into_iter().map(|(state, count)| (state << 2, count)).collect();
+    }
+    Ok(solutions)
+}
+
+/// Position of the ')' matching a '(' just before position `from`
+fn matching_close(state: u64, from: usize, n: usize) -> usize {
+    let mut depth = 1;
+    for k in from..=n {
+        match plug(state, k) {
+            OPEN => depth += 1,
+            CLOSE => {
+                depth -= 1;
+                if depth == 0 {
+                    return k;
+                }
+            }
+            _ => {}
+        }
+    }
+    unreachable!("unbalanced frontier state {:b}", state)
+}
+
+/// Position of the '(' matching a ')' at position `to`
+fn matching_open(state: u64, to: usize) -> usize {
+    let mut depth = 1;
+    for k in (0..to).rev() {
+        match plug(state, k) {
+            CLOSE => depth += 1,
+            OPEN => {
+                depth -= 1;
+                if depth == 0 {
+                    return k;
+                }
+            }
+            _ => {}
+        }
+    }
+    unreachable!("unbalanced frontier state {:b}", state)
+}
@@ -0,0 +1,265 @@
+/*-------------------------------------
+  Solution visitors
+
+    check_board calls a SolutionVisitor with every cycle it completes, and with every vertice it
+    backtracks from. The visitor decides what to do with them - keep them, print them, write them
+    to a file - and whether the search should go on.
+-------------------------------------*/
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::cycle::Cycle;
+use crate::metrics::Metrics;
+
+/// What the search should do after calling a visitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visit {
+    Continue,
+    Stop,
+}
+
+/// Called by the search for every solution found
+pub trait SolutionVisitor {
+    /// A solution: the vertices of the cycle in the order visited, starting in vertice 0
+    /// (the edge back to 0 is not repeated). `weight` is the number of loops it stands for: 1, unless the search
+    /// counts the rotated and mirrored copies of a loop from one of them (see `counts_only`).
+    fn solution(&mut self, cycle: &[usize], weight: u128, metrics: &Metrics) -> Visit;
+
+    /// Does the visitor only count the solutions? Then the search may hand it one loop of every class of rotated
+    /// and mirrored copies, weighted by the number of copies, instead of every loop (see `Board::set_folding`)
+    fn counts_only(&self) -> bool {
+        false
+    }
+
+    /// The search backtracks from the last vertice of `path`, having tried every way to go on from there
+    fn backtrack(&mut self, _path: &[usize], _metrics: &Metrics) -> Visit {
+        Visit::Continue
+    }
+}
+
+/// Count the solutions, each with its weight
+#[derive(Debug, Default)]
+pub struct CountSolutions {
+    pub solutions: u128,
+}
+
+impl SolutionVisitor for CountSolutions {
+    fn solution(&mut self, _cycle: &[usize], weight: u128, _metrics: &Metrics) -> Visit {
+        self.solutions += weight;
+        Visit::Continue
+    }
+
+    fn counts_only(&self) -> bool {
+        true
+    }
+}
+
+/// Keep the first `max_cycles` solutions, and stop the search there if `stop_when_full` is set
+pub struct CollectCycles {
+    pub max_cycles: usize,
+    pub stop_when_full: bool,
+    pub cycles: Vec<Cycle>,
+}
+
+impl CollectCycles {
+    pub fn new(max_cycles: usize) -> Self {
+        CollectCycles {
+            max_cycles,
+            stop_when_full: false,
+            cycles: vec![],
+        }
+    }
+}
+
+impl SolutionVisitor for CollectCycles {
+    fn solution(&mut self, cycle: &[usize], _weight: u128, _metrics: &Metrics) -> Visit {
+        if self.cycles.len() < self.max_cycles {
+            self.cycles.push(Cycle::new(cycle.to_vec()));
+        }
+        if self.stop_when_full && self.cycles.len() >= self.max_cycles {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        }
+    }
+}
+
+/// Print the elapsed time to stderr for every `every` solutions
+pub struct ReportProgress {
+    pub every: u128,
+    /// the solutions seen so far
+    solutions: u128,
+}
+
+impl Default for ReportProgress {
+    fn default() -> Self {
+        ReportProgress {
+            every: 10000,
+            solutions: 0,
+        }
+    }
+}
+
+impl SolutionVisitor for ReportProgress {
+    fn solution(&mut self, _cycle: &[usize], weight: u128, metrics: &Metrics) -> Visit {
+        // (a solution may stand for several, so the count can step over a multiple of `every`)
+        let reported = self.solutions / self.every;
+        self.solutions += weight;
+        if self.solutions / self.every != reported {
+            eprintln!("{:?}: {} solutions", metrics.run_duration.elapsed(), self.solutions);
+        }
+        Visit::Continue
+    }
+
+    fn counts_only(&self) -> bool {
+        true
+    }
+}
+
+/// Stop the search once `budget` has passed since the visitor was created
+pub struct StopAfter {
+    pub deadline: Instant,
+    /// set when the search was stopped before it was complete
+    pub timed_out: bool,
+    backtracks: u32,
+}
+
+impl StopAfter {
+    pub fn new(budget: Duration) -> Self {
+        StopAfter {
+            deadline: Instant::now() + budget,
+            timed_out: false,
+            backtracks: 0,
+        }
+    }
+}
+
+impl SolutionVisitor for StopAfter {
+    fn solution(&mut self, _cycle: &[usize], _weight: u128, _metrics: &Metrics) -> Visit {
+        Visit::Continue
+    }
+
+    fn counts_only(&self) -> bool {
+        true
+    }
+
+    fn backtrack(&mut self, _path: &[usize], _metrics: &Metrics) -> Visit {
+        // reading the clock on every backtrack would slow the search down noticeably
+        self.backtracks = self.backtracks.wrapping_add(1);
+        if self.backtracks.is_multiple_of(4096) && Instant::now() >= self.deadline {
+            self.timed_out = true;
+        }
+        if self.timed_out {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        }
+    }
+}
+
+/// Write the first `max_cycles` solutions, one line each (`0 1 5 4 0`) unless another format is set,
+/// and stop the search at the first write error
+pub struct WriteCycles<W: Write> {
+    pub writer: W,
+    pub max_cycles: usize,
+    pub written: usize,
+    pub error: Option<std::io::Error>,
+    format: Box<dyn Fn(&Cycle) -> String>,
+}
+
+impl<W: Write> WriteCycles<W> {
+    pub fn new(writer: W, max_cycles: usize) -> Self {
+        WriteCycles {
+            writer,
+            max_cycles,
+            written: 0,
+            error: None,
+            format: Box::new(|cycle| format!("{}\n", cycle)),
+        }
+    }
+
+    /// Write each solution as `format` has it (such as `render_cycle`) instead of as a line of vertices
+    pub fn with_format<F: Fn(&Cycle) -> String + 'static>(mut self, format: F) -> Self {
+        self.format = Box::new(format);
+        self
+    }
+}
+
+impl<W: Write> SolutionVisitor for WriteCycles<W> {
+    fn solution(&mut self, cycle: &[usize], _weight: u128, _metrics: &Metrics) -> Visit {
+        if self.written >= self.max_cycles {
+            return Visit::Continue;
+        }
+        self.written += 1;
+        match self.writer.write_all((self.format)(&Cycle::new(cycle.to_vec())).as_bytes()) {
+            Ok(()) => Visit::Continue,
+            Err(error) => {
+                self.error = Some(error);
+                Visit::Stop
+            }
+        }
+    }
+}
+
+/// Two visitors, called one after the other; the search stops if either of them says so
+impl<A: SolutionVisitor, B: SolutionVisitor> SolutionVisitor for (A, B) {
+    fn solution(&mut self, cycle: &[usize], weight: u128, metrics: &Metrics) -> Visit {
+        let first = self.0.solution(cycle, weight, metrics);
+        let second = self.1.solution(cycle, weight, metrics);
+        if first == Visit::Stop || second == Visit::Stop {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        }
+    }
+
+    fn counts_only(&self) -> bool {
+        self.0.counts_only() && self.1.counts_only()
+    }
+
+    fn backtrack(&mut self, path: &[usize], metrics: &Metrics) -> Visit {
+        let first = self.0.backtrack(path, metrics);
+        let second = self.1.backtrack(path, metrics);
+        if first == Visit::Stop || second == Visit::Stop {
+            Visit::Stop
+        } else {
+            Visit::Continue
+        }
+    }
+}
+
+/// An optional visitor: `None` does nothing
+impl<V: SolutionVisitor> SolutionVisitor for Option<V> {
+    fn solution(&mut self, cycle: &[usize], weight: u128, metrics: &Metrics) -> Visit {
+        match self {
+            Some(visitor) => visitor.solution(cycle, weight, metrics),
+            None => Visit::Continue,
+        }
+    }
+
+    fn counts_only(&self) -> bool {
+        self.as_ref().is_none_or(|visitor| visitor.counts_only())
+    }
+
+    fn backtrack(&mut self, path: &[usize], metrics: &Metrics) -> Visit {
+        match self {
+            Some(visitor) => visitor.backtrack(path, metrics),
+            None => Visit::Continue,
+        }
+    }
+}
+
+impl<V: SolutionVisitor + ?Sized> SolutionVisitor for &mut V {
+    fn solution(&mut self, cycle: &[usize], weight: u128, metrics: &Metrics) -> Visit {
+        (**self).solution(cycle, weight, metrics)
+    }
+
+    fn counts_only(&self) -> bool {
+        (**self).counts_only()
+    }
+
+    fn backtrack(&mut self, path: &[usize], metrics: &Metrics) -> Visit {
+        (**self).backtrack(path, metrics)
+    }
+}
@@ -0,0 +1,81 @@
+//! Checkpoints written and read back, and a search interrupted halfway and resumed from its checkpoint,
+//! which has to end with the same totals as the search run in one go.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use roundtrip::{
+    check_positions, collect_positions, count_with_checkpoints, default_split_depth, resume_from_checkpoint, Checkpoint, Count, Error,
+    Lattice, Metrics,
+};
+
+/// A checkpoint file of its own for every test, as the tests run in parallel
+fn checkpoint_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("roundtrip-{}-{}.txt", name, std::process::id()))
+}
+
+#[test]
+fn checkpoint_round_trip() {
+    let lattice = Lattice::new(6, 7).unwrap();
+    let split_depth = default_split_depth(&lattice);
+    let mut metrics = Metrics::new();
+    let positions = collect_positions(&lattice, split_depth, &mut metrics).unwrap();
+    assert!(!positions.is_empty());
+    let checkpoint = Checkpoint {
+        lattice,
+        split_depth,
+        metrics,
+        positions,
+    };
+    let path = checkpoint_path("round-trip");
+    checkpoint.save(&path).unwrap();
+    let loaded = Checkpoint::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.lattice, checkpoint.lattice);
+    assert_eq!(loaded.split_depth, checkpoint.split_depth);
+    assert_eq!(loaded.positions, checkpoint.positions);
+    assert_eq!(loaded.metrics.check_counter, checkpoint.metrics.check_counter);
+    assert_eq!(loaded.metrics.fail_counter_1, checkpoint.metrics.fail_counter_1);
+    assert_eq!(loaded.metrics.fail_counter_2, checkpoint.metrics.fail_counter_2);
+    assert_eq!(loaded.metrics.fail_counter_3, checkpoint.metrics.fail_counter_3);
+    assert_eq!(loaded.metrics.solutions_counter, checkpoint.metrics.solutions_counter);
+}
+
+#[test]
+fn resumed_search_gives_the_same_totals() {
+    let lattice = Lattice::new(6, 7).unwrap();
+    let split_depth = default_split_depth(&lattice);
+
+    // the search in one go
+    let path = checkpoint_path("in-one-go");
+    let mut whole = Metrics::new();
+    let count = count_with_checkpoints(&lattice, &mut whole, 2, split_depth, &path, Duration::from_secs(60)).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(count, Count::from(5320u32));
+
+    // the search interrupted after half of the positions ...
+    let mut metrics = Metrics::new();
+    let positions = collect_positions(&lattice, split_depth, &mut metrics).unwrap();
+    let (searched, remaining) = positions.split_at(positions.len() / 2);
+    check_positions(&lattice, searched, 2, &mut metrics, |_, _| Ok::<(), Error>(())).unwrap();
+    let path = checkpoint_path("interrupted");
+    Checkpoint {
+        lattice,
+        split_depth,
+        metrics,
+        positions: remaining.to_vec(),
+    }
+    .save(&path)
+    .unwrap();
+
+    // ... and resumed from its checkpoint
+    let mut resumed = Metrics::new();
+    let (resumed_lattice, resumed_count) = resume_from_checkpoint(&path, &mut resumed, 2, Duration::from_secs(60)).unwrap();
+    assert!(Checkpoint::load(&path).unwrap().positions.is_empty());
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(resumed_lattice, lattice);
+    assert_eq!(resumed_count, count);
+    assert_eq!(resumed.solutions_counter, whole.solutions_counter);
+    assert_eq!(resumed.check_counter, whole.check_counter);
+}
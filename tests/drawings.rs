@@ -0,0 +1,120 @@
+//! Solutions and puzzles drawn with box drawing characters, compared with the drawings they should give -
+//! with the tiles inside the loop shaded (found by counting the loop edges to their left) and without -
+//! and as SVG sheets, checked for the elements every board should have.
+
+use roundtrip::{parse_puzzle, render_cycle, render_puzzle, svg_puzzle, svg_sheet, svg_solution, Cycle, Metrics, Puzzle, SvgStyle};
+
+#[test]
+fn loop_with_the_inside_shaded() {
+    // the 3 x 4 solution drawn in the doc of the render module
+    let cycle = Cycle::new(vec![0, 1, 2, 5, 4, 7, 8, 11, 10, 9, 6, 3]);
+    let shaded = "\
+┌───┐
+│░░░│
+│░┌─┘
+│░│
+│░└─┐
+│░░░│
+└───┘
+";
+    let plain = "\
+┌───┐
+│   │
+│ ┌─┘
+│ │
+│ └─┐
+│   │
+└───┘
+";
+    assert_eq!(render_cycle(3, 4, &cycle, true), shaded);
+    assert_eq!(render_cycle(3, 4, &cycle, false), plain);
+}
+
+#[test]
+fn shading_goes_outside_and_back_in() {
+    // on the top row the tile between the two arms of the loop is outside, the ones on either side inside
+    let cycle = Cycle::new(vec![0, 1, 5, 6, 2, 3, 7, 11, 10, 9, 8, 4]);
+    let shaded = "\
+┌─┐ ┌─┐
+│░│ │░│
+│░└─┘░│
+│░░░░░│
+└─────┘
+";
+    let plain = "\
+┌─┐ ┌─┐
+│ │ │ │
+│ └─┘ │
+│     │
+└─────┘
+";
+    assert_eq!(render_cycle(4, 3, &cycle, true), shaded);
+    assert_eq!(render_cycle(4, 3, &cycle, false), plain);
+}
+
+#[test]
+fn puzzle_with_given_and_forbidden_edges() {
+    let puzzle = parse_puzzle(".-. . .\n|     x\n. . . .\n\n. . . .\n").unwrap();
+    assert_eq!(render_puzzle(&puzzle), "┌─╴ · ·\n│     ×\n╵ · · ·\n\n· · · ·\n");
+}
+
+/// A 6 x 4 puzzle with three clues and a forbidden edge, which can be completed in several ways
+fn clued_puzzle() -> Puzzle {
+    let mut puzzle = Puzzle::new(6, 4).unwrap();
+    puzzle.add_given_edge(0, 1).unwrap();
+    puzzle.add_given_edge(1, 2).unwrap();
+    puzzle.add_given_edge(6, 12).unwrap();
+    puzzle.add_forbidden_edge(9, 15).unwrap();
+    puzzle
+}
+
+fn elements(svg: &str, tag: &str) -> usize {
+    svg.matches(&format!("<{} ", tag)).count()
+}
+
+#[test]
+fn svg_of_a_single_solution() {
+    let puzzle = clued_puzzle();
+    let solutions = puzzle.solve(1, &mut Metrics::new()).unwrap();
+    let svg = svg_solution(&puzzle, &solutions.cycles[0], &SvgStyle::default());
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="144" height="96""#), "{}", svg);
+    assert_eq!(elements(&svg, "polygon"), 1); // the loop
+    assert_eq!(elements(&svg, "circle"), 24); // the dots
+    assert_eq!(elements(&svg, "line"), 3); // the clues
+    assert_eq!(elements(&svg, "path"), 1); // the forbidden edge
+    assert!(svg.contains(r#"fill="none""#));
+
+    // the puzzle on its own has no loop, but the same dots and clues
+    let svg = svg_puzzle(&puzzle, &SvgStyle::default());
+    assert_eq!(elements(&svg, "polygon"), 0);
+    assert_eq!(elements(&svg, "circle"), 24);
+    assert_eq!((elements(&svg, "line"), elements(&svg, "path")), (3, 1));
+}
+
+#[test]
+fn svg_sheet_of_several_solutions() {
+    let puzzle = Puzzle::new(4, 4).unwrap();
+    let cycles = puzzle.solve(usize::MAX, &mut Metrics::new()).unwrap().cycles;
+    assert_eq!(cycles.len(), 6);
+    let style = SvgStyle {
+        fill_colour: Some("#dddddd".to_string()),
+        ..SvgStyle::default()
+    };
+    // four boards to a row: two rows, the second one half full
+    let svg = svg_sheet(&puzzle, &cycles, 4, &style);
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="384" height="192""#), "{}", svg);
+    assert_eq!(svg.matches("<g>").count(), 6);
+    assert_eq!(elements(&svg, "polygon"), 6);
+    assert_eq!(elements(&svg, "circle"), 6 * 16);
+    assert_eq!(svg.matches(r##"fill="#dddddd""##).count(), 6);
+
+    // with clues, every board has them
+    let puzzle = clued_puzzle();
+    let cycles = puzzle.solve(usize::MAX, &mut Metrics::new()).unwrap().cycles;
+    assert!(cycles.len() > 1);
+    let svg = svg_sheet(&puzzle, &cycles, 2, &SvgStyle::default());
+    assert_eq!(elements(&svg, "polygon"), cycles.len());
+    assert_eq!(elements(&svg, "circle"), cycles.len() * 24);
+    assert_eq!(elements(&svg, "line"), cycles.len() * 3);
+    assert_eq!(elements(&svg, "path"), cycles.len());
+}
@@ -0,0 +1,217 @@
+//! The counts of the search checked against the published numbers of Hamiltonian cycles on grid graphs
+//! (OEIS A003763 for the 2n x 2n boards and A006864, A006865, A006866 for 4 x m, 5 x m and 6 x m),
+//! so a change to the pruning in check_board can not silently drop solutions.
+
+use roundtrip::{
+    check_board, count_board, count_cycles, count_cycles_with, count_parallel, count_with_metrics, count_with_visitor, cross_check,
+    edge_bits, initialize_board, verify_with_visitor, Algorithm, CollectCycles, Count, CountSolutions, CountSymmetric, Error, Lattice,
+    Metrics, OnlyCanonical, Puzzle, Symmetry,
+};
+
+fn search(n: usize, m: usize) -> Count {
+    count_cycles(n, m).unwrap_or_else(|e| panic!("{} x {}: {}", n, m, e))
+}
+
+#[test]
+fn square_boards() {
+    assert_eq!(search(4, 4), Count::from(6u32));
+    assert_eq!(search(6, 6), Count::from(1072u32));
+}
+
+#[test]
+fn two_rows() {
+    // the only loop runs around the rim (on 2 x 64 the board fills a u128, beyond that the sets take more words)
+    for m in (6..=20).chain([64, 65, 100]) {
+        assert_eq!(search(2, m), Count::from(1u32), "2 x {}", m);
+    }
+}
+
+#[test]
+fn three_rows() {
+    // 2^(m/2 - 1) loops on a 3 x m board with m even
+    for m in (4..=16).step_by(2) {
+        assert_eq!(search(3, m), Count::from(1u32 << (m / 2 - 1)), "3 x {}", m);
+    }
+}
+
+#[test]
+fn four_rows() {
+    let known: [u32; 9] = [6, 14, 37, 92, 236, 596, 1517, 3846, 9770];
+    for (k, &count) in known.iter().enumerate() {
+        assert_eq!(search(4, k + 4), Count::from(count), "4 x {}", k + 4);
+    }
+}
+
+#[test]
+fn five_and_six_rows() {
+    assert_eq!(search(5, 6), Count::from(154u32));
+    assert_eq!(search(5, 8), Count::from(1696u32));
+    assert_eq!(search(6, 7), Count::from(5320u32));
+    assert_eq!(search(6, 8), Count::from(32675u32));
+}
+
+#[test]
+fn transposed_sizes() {
+    // an m x n board has the same loops as the n x m board: count_cycles wants the high and thin one,
+    // and a wide board is counted as an empty puzzle (which is solved on its transpose)
+    for (n, m, count) in [(4, 6, 37u32), (3, 8, 8), (5, 6, 154), (4, 5, 14), (2, 7, 1)] {
+        assert_eq!(count_cycles(m, n), Err(Error::NotHighAndThin { n: m, m: n }));
+        let wide = Puzzle::new(m, n).unwrap().solve(0, &mut Metrics::new()).unwrap();
+        assert_eq!(wide.count, Count::from(count), "{} x {}", m, n);
+        assert_eq!(search(n, m), Count::from(count), "{} x {}", n, m);
+        assert_eq!(search(n, m), count_cycles_with(n, m, Algorithm::TransferMatrix).unwrap(), "{} x {}", n, m);
+    }
+}
+
+#[test]
+fn check_board_counts_every_solution_once() {
+    let lattice = Lattice::new(4, 6).unwrap();
+    let mut board = initialize_board(&lattice).unwrap();
+    let mut metrics = Metrics::new();
+    let mut counter = CountSolutions::default();
+    check_board(&mut board, &mut vec![], 0, &mut metrics, &mut counter);
+    assert_eq!(counter.solutions, 37);
+    // the board is left as it was found, so searching it again finds the same solutions
+    let mut again = CountSolutions::default();
+    check_board(&mut board, &mut vec![], 0, &mut Metrics::new(), &mut again);
+    assert_eq!(again.solutions, 37);
+    assert_eq!(lattice.cycles().unwrap().count(), 37);
+}
+
+#[test]
+fn the_transfer_matrix_and_the_threads_agree_with_the_search() {
+    for (n, m) in [(4, 10), (5, 8), (6, 6)] {
+        let count = search(n, m);
+        assert_eq!(count_cycles_with(n, m, Algorithm::TransferMatrix).unwrap(), count, "{} x {}", n, m);
+        let lattice = Lattice::new(n, m).unwrap();
+        assert_eq!(count_parallel(&lattice, &mut Metrics::new(), 4, n * m / 4).unwrap(), count, "{} x {}", n, m);
+    }
+}
+
+#[test]
+fn invalid_sizes() {
+    assert_eq!(count_cycles(3, 3), Err(Error::TooSmall { n: 3, m: 3 }));
+    assert_eq!(count_cycles(3, 5), Err(Error::OddSize { n: 3, m: 5 }));
+}
+
+#[test]
+fn the_reference_counter_agrees_with_the_search() {
+    assert_eq!(count_cycles_with(4, 4, Algorithm::Reference), Ok(Count::from(6u32)));
+    let mut sizes = vec![];
+    assert_eq!(cross_check(5, 7, |n, m| sizes.push((n, m))), Ok(vec![]));
+    assert_eq!(sizes.len(), 9);
+}
+
+#[test]
+fn every_path_through_all_vertices_closes_the_loop() {
+    for (n, m) in [(2, 8), (3, 8), (4, 7), (5, 6), (6, 6)] {
+        let lattice = Lattice::new(n, m).unwrap();
+        let mut metrics = Metrics::new();
+        let count = verify_with_visitor(&lattice, &mut metrics, &mut CountSolutions::default()).unwrap();
+        assert_eq!(count, search(n, m), "{} x {}", n, m);
+        assert_eq!((metrics.fail_counter_1, metrics.exception_counter), (0, 0), "{} x {}", n, m);
+    }
+}
+
+#[test]
+fn solutions_up_to_rotations_and_reflections() {
+    // OEIS A209077: 1, 2, 149, ... on the 2n x 2n boards
+    for (n, m, classes) in [(4, 4, 2u32), (6, 6, 149), (2, 8, 1)] {
+        let lattice = Lattice::new(n, m).unwrap();
+        let mut symmetric = CountSymmetric::new(&lattice);
+        let count = count_with_visitor(&lattice, &mut Metrics::new(), &mut symmetric).unwrap();
+        assert_eq!(symmetric.fixed[0].0, Symmetry::Identity);
+        assert_eq!(Count::from(symmetric.fixed[0].1), count, "{} x {}", n, m);
+        assert_eq!(symmetric.classes(), Count::from(classes), "{} x {}", n, m);
+    }
+}
+
+#[test]
+fn one_canonical_solution_of_every_class() {
+    for (n, m) in [(4, 4), (4, 6), (5, 6), (6, 6)] {
+        let lattice = Lattice::new(n, m).unwrap();
+        let mut symmetric = CountSymmetric::new(&lattice);
+        let mut canonical = OnlyCanonical::new(&lattice, CollectCycles::new(usize::MAX));
+        let count = count_with_visitor(&lattice, &mut Metrics::new(), &mut (&mut symmetric, &mut canonical)).unwrap();
+        assert_eq!(Count::from(canonical.canonical), symmetric.classes(), "{} x {}", n, m);
+        // the rotated and flipped copies of the canonical solutions are all the solutions, each of them once
+        let mut images: Vec<Vec<bool>> = vec![];
+        for cycle in &canonical.visitor.cycles {
+            for symmetry in Symmetry::group(&lattice) {
+                let image: Vec<usize> = cycle.vertices.iter().map(|&v| symmetry.map(&lattice, v)).collect();
+                images.push(edge_bits(&lattice, &image));
+            }
+        }
+        images.sort();
+        images.dedup();
+        assert_eq!(Count::from(images.len() as u128), count, "{} x {}", n, m);
+    }
+}
+
+#[test]
+fn the_symmetric_search_finds_the_solutions_left_unchanged() {
+    for (n, m) in [(4, 6), (5, 6), (6, 6), (4, 8)] {
+        let lattice = Lattice::new(n, m).unwrap();
+        let mut symmetric = CountSymmetric::new(&lattice);
+        count_with_visitor(&lattice, &mut Metrics::new(), &mut symmetric).unwrap();
+        for &(symmetry, fixed) in &symmetric.fixed {
+            let mut board = initialize_board(&lattice).unwrap();
+            board.require_symmetry(symmetry).unwrap();
+            let mut collect = CollectCycles::new(usize::MAX);
+            let count = count_board(&mut board, &mut Metrics::new(), &mut collect);
+            assert_eq!(count, Count::from(fixed), "{} x {}, {}", n, m, symmetry);
+            assert!(collect.cycles.iter().all(|cycle| symmetry.fixes(&lattice, &cycle.vertices)));
+        }
+    }
+    // the half turn and the two mirrors of the 8 x 8 board, adding up to 580717 classes by Burnside's lemma (OEIS A209077)
+    let lattice = Lattice::new(8, 8).unwrap();
+    for (symmetry, fixed) in [(Symmetry::Rotate180, 1504u32), (Symmetry::MirrorLeftRight, 2828), (Symmetry::Rotate90, 0)] {
+        let mut board = initialize_board(&lattice).unwrap();
+        board.require_symmetry(symmetry).unwrap();
+        assert_eq!(count_board(&mut board, &mut Metrics::new(), &mut CountSolutions::default()), Count::from(fixed), "8 x 8, {}", symmetry);
+    }
+    let mut board = initialize_board(&Lattice::new(4, 6).unwrap()).unwrap();
+    assert_eq!(board.require_symmetry(Symmetry::MirrorDiagonal), Err(Error::NotSquare { n: 4, m: 6 }));
+}
+
+#[test]
+fn symmetry_folding_counts_the_same_solutions_with_less_work() {
+    for (n, m) in [(2, 8), (3, 6), (4, 4), (4, 12), (5, 6), (6, 6), (5, 8), (6, 7)] {
+        let lattice = Lattice::new(n, m).unwrap();
+        let mut full = Metrics::new();
+        let mut board = initialize_board(&lattice).unwrap();
+        board.set_folding(false);
+        let count = count_board(&mut board, &mut full, &mut CountSolutions::default());
+        let mut folded = Metrics::new();
+        assert_eq!(count_with_metrics(&lattice, &mut folded).unwrap(), count, "{} x {}", n, m);
+        assert_eq!(folded.solutions_counter, full.solutions_counter, "{} x {}", n, m);
+        if n > 2 {
+            // (a 2 x m board has only one loop)
+            assert!(folded.check_counter < full.check_counter, "{} x {}", n, m);
+        }
+    }
+    // a square board has 8 symmetries, and less than half the checks are left
+    // (a visitor listing the solutions is handed every one of them, copies included)
+    let lattice = Lattice::new(6, 6).unwrap();
+    let (mut full, mut folded) = (Metrics::new(), Metrics::new());
+    let mut collect = CollectCycles::new(usize::MAX);
+    count_with_visitor(&lattice, &mut full, &mut collect).unwrap();
+    assert_eq!(collect.cycles.len(), 1072);
+    count_with_metrics(&lattice, &mut folded).unwrap();
+    assert!(2 * folded.check_counter < full.check_counter);
+}
+
+#[test]
+fn connectivity_pruning_counts_the_same_solutions_with_less_work() {
+    for (n, m) in [(4, 6), (5, 6), (6, 6), (5, 8), (6, 7)] {
+        let lattice = Lattice::new(n, m).unwrap();
+        let mut unpruned = Metrics::new();
+        let mut board = initialize_board(&lattice).unwrap();
+        board.set_connectivity(false);
+        let count = count_board(&mut board, &mut unpruned, &mut CountSolutions::default());
+        let mut pruned = Metrics::new();
+        let mut board = initialize_board(&lattice).unwrap();
+        assert_eq!(count_board(&mut board, &mut pruned, &mut CountSolutions::default()), count, "{} x {}", n, m);
+        assert!(pruned.check_counter < unpruned.check_counter, "{} x {}", n, m);
+    }
+}
@@ -0,0 +1,174 @@
+//! Puzzles completed by the search, checked against a brute force count of the loops through the given edges
+//! that avoid the forbidden ones - on boards high and thin as well as wide ones (which are solved transposed).
+
+use roundtrip::{Count, Metrics, Puzzle};
+
+/// Every loop on the puzzle's board found by walking all paths from vertex 0, without any pruning
+fn brute_force(puzzle: &Puzzle) -> u64 {
+    let (n, m) = (puzzle.n(), puzzle.m());
+    let has = |edges: &[(usize, usize)], a: usize, b: usize| edges.contains(&(a, b)) || edges.contains(&(b, a));
+    let neighbours = |v: usize| {
+        let mut next = vec![];
+        if v >= n {
+            next.push(v - n);
+        }
+        if !v.is_multiple_of(n) {
+            next.push(v - 1);
+        }
+        if !(v + 1).is_multiple_of(n) {
+            next.push(v + 1);
+        }
+        if v + n < n * m {
+            next.push(v + n);
+        }
+        next.into_iter().filter(|&w| !has(puzzle.forbidden_edges(), v, w)).collect::<Vec<_>>()
+    };
+    let closes = |path: &[usize]| {
+        let last = path[path.len() - 1];
+        if !neighbours(last).contains(&0) {
+            return false;
+        }
+        puzzle.given_edges().iter().all(|&(a, b)| {
+            path.windows(2).any(|w| (w[0], w[1]) == (a, b) || (w[0], w[1]) == (b, a)) || (last, 0) == (a, b) || (last, 0) == (b, a)
+        })
+    };
+    fn walk(path: &mut Vec<usize>, visited: &mut [bool], neighbours: &dyn Fn(usize) -> Vec<usize>, closes: &dyn Fn(&[usize]) -> bool) -> u64 {
+        if path.len() == visited.len() {
+            return closes(path) as u64;
+        }
+        let mut count = 0;
+        for w in neighbours(path[path.len() - 1]) {
+            if !visited[w] {
+                visited[w] = true;
+                path.push(w);
+                count += walk(path, visited, neighbours, closes);
+                path.pop();
+                visited[w] = false;
+            }
+        }
+        count
+    }
+    let mut visited = vec![false; n * m];
+    visited[0] = true;
+    walk(&mut vec![0], &mut visited, &neighbours, &closes) / 2 // every loop is walked in both directions
+}
+
+fn puzzle(n: usize, m: usize, given: &[(usize, usize)], forbidden: &[(usize, usize)]) -> Puzzle {
+    let mut puzzle = Puzzle::new(n, m).unwrap();
+    for &(a, b) in given {
+        puzzle.add_given_edge(a, b).unwrap();
+    }
+    for &(a, b) in forbidden {
+        puzzle.add_forbidden_edge(a, b).unwrap();
+    }
+    puzzle
+}
+
+fn solve(puzzle: &Puzzle) -> Count {
+    puzzle.solve(usize::MAX, &mut Metrics::new()).unwrap().count
+}
+
+#[test]
+fn given_edges_completions() {
+    let wide = puzzle(6, 4, &[(0, 1), (1, 2), (6, 12)], &[]);
+    assert_eq!(brute_force(&wide), 13);
+    assert_eq!(solve(&wide), Count::from(13u32));
+
+    let puzzles = [
+        puzzle(4, 4, &[(5, 6)], &[]),
+        puzzle(4, 4, &[(5, 9), (6, 10)], &[]),
+        puzzle(4, 6, &[(1, 5), (9, 10), (14, 18)], &[]),
+        puzzle(6, 4, &[(7, 8), (15, 16)], &[]),
+        puzzle(5, 4, &[(6, 7), (12, 17)], &[]),
+        puzzle(4, 5, &[(5, 9)], &[(10, 11), (13, 17)]),
+        puzzle(6, 4, &[(2, 3)], &[(8, 14), (9, 10)]),
+    ];
+    for puzzle in &puzzles {
+        assert_eq!(solve(puzzle), Count::from(brute_force(puzzle)), "{:?}", puzzle);
+    }
+}
+
+#[test]
+fn completions_use_the_given_edges() {
+    let wide = puzzle(6, 4, &[(0, 1), (1, 2), (6, 12)], &[(9, 15)]);
+    let solutions = wide.solve(usize::MAX, &mut Metrics::new()).unwrap();
+    assert_eq!(solutions.count, Count::from(brute_force(&wide)));
+    assert_eq!(Count::from(solutions.cycles.len() as u64), solutions.count);
+    for cycle in &solutions.cycles {
+        assert_eq!(cycle.vertices.len(), 24);
+        assert!(wide.given_edges().iter().all(|&(a, b)| cycle.has_edge(a, b)));
+        assert!(!cycle.has_edge(9, 15));
+    }
+}
+
+#[test]
+fn forbidden_closing_edge() {
+    // the loop closes with the edge between vertex 0 and the vertex below it ...
+    let tall = puzzle(4, 4, &[], &[(0, 4)]);
+    assert_eq!(brute_force(&tall), 0);
+    assert_eq!(solve(&tall), Count::from(0u32));
+    // ... which a wide puzzle has as the edge between vertex 0 and the vertex to its right
+    let wide = puzzle(6, 3, &[], &[(0, 1)]);
+    assert_eq!(brute_force(&wide), 0);
+    assert_eq!(solve(&wide), Count::from(0u32));
+}
+
+/// All edges between neighbour vertices of an n x m board
+fn all_edges(n: usize, m: usize) -> Vec<(usize, usize)> {
+    let mut edges = vec![];
+    for v in 0..n * m {
+        if !(v + 1).is_multiple_of(n) {
+            edges.push((v, v + 1));
+        }
+        if v + n < n * m {
+            edges.push((v, v + n));
+        }
+    }
+    edges
+}
+
+#[test]
+fn random_puzzles_match_brute_force() {
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut random = move |below: u64| {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 33) % below
+    };
+    for &(n, m) in &[(3, 4), (4, 3), (4, 4), (4, 5), (5, 4), (3, 6), (6, 3)] {
+        for _ in 0..40 {
+            let (mut given, mut forbidden) = (vec![], vec![]);
+            for edge in all_edges(n, m) {
+                match random(10) {
+                    0 => given.push(edge),
+                    1 => forbidden.push(edge),
+                    _ => {}
+                }
+            }
+            let puzzle = puzzle(n, m, &given, &forbidden);
+            assert_eq!(solve(&puzzle), Count::from(brute_force(&puzzle)), "{:?}", puzzle);
+        }
+    }
+}
+
+/// A 3 x m puzzle with the loop drawn down to row `rows`: along the top row, down the left column, and down the right
+/// column picking up the middle column two vertices at a time - only the rows below are left to the search
+fn drawn_down_to(m: usize, rows: usize) -> Puzzle {
+    let mut given = vec![(0, 1), (1, 2)];
+    for row in 0..rows - 1 {
+        given.push((3 * row, 3 * row + 3));
+    }
+    for row in (1..rows - 1).step_by(2) {
+        let right = 3 * row + 2;
+        given.extend([(right - 3, right), (right, right - 1), (right - 1, right + 2), (right + 2, right + 3)]);
+    }
+    puzzle(3, m, &given, &[])
+}
+
+#[test]
+fn boards_over_128_vertices() {
+    // the same 3 x 9 block left open at the bottom of a board of 60 vertices and one of 132, whose sets take more than a u128
+    let narrow = drawn_down_to(20, 11);
+    let wide = drawn_down_to(44, 35);
+    assert_eq!(solve(&narrow), Count::from(16u32));
+    assert_eq!(solve(&wide), Count::from(16u32));
+}
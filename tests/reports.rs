@@ -0,0 +1,78 @@
+//! The JSON report of a run: the keys in their order, and which of them are null. Scripts read these reports,
+//! so a key renamed, moved or dropped should fail here before it breaks them.
+
+use std::time::Duration;
+
+use roundtrip::{count_with_metrics, Algorithm, Count, Lattice, Metrics, Report};
+
+const KEYS: [&str; 16] = [
+    "n",
+    "m",
+    "count",
+    "classes",
+    "algorithm",
+    "wall_time_seconds",
+    "check_counter",
+    "fail_counter_1",
+    "fail_counter_2",
+    "fail_counter_3",
+    "exception_counter",
+    "solutions_counter",
+    "visited_vertices",
+    "visited_rim_vertices",
+    "nodes_per_second",
+    "version",
+];
+
+/// The keys and values of a JSON object on a single line (with no commas or colons inside the values)
+fn fields(json: &str) -> Vec<(String, String)> {
+    let inner = json.strip_prefix('{').and_then(|json| json.strip_suffix('}')).expect(json);
+    inner
+        .split(',')
+        .map(|field| {
+            let (key, value) = field.split_once(':').expect(field);
+            (key.trim_matches('"').to_string(), value.to_string())
+        })
+        .collect()
+}
+
+fn report(algorithm: Algorithm, metrics: Metrics) -> Report {
+    Report {
+        n: 4,
+        m: 6,
+        algorithm,
+        count: Count::from(37u32),
+        classes: None,
+        metrics,
+        wall_time: Duration::from_millis(5),
+    }
+}
+
+#[test]
+fn search_report_has_every_counter() {
+    let mut metrics = Metrics::new();
+    count_with_metrics(&Lattice::new(4, 6).unwrap(), &mut metrics).unwrap();
+    let fields = fields(&report(Algorithm::Search, metrics.clone()).to_json());
+    assert_eq!(fields.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), KEYS);
+    let value = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str()).unwrap();
+    assert_eq!(value("count"), "37");
+    assert_eq!(value("classes"), "null");
+    assert_eq!(value("algorithm"), "\"search\"");
+    assert_eq!(value("wall_time_seconds"), "0.005000");
+    assert_eq!(value("check_counter"), metrics.check_counter.to_string());
+    assert_eq!(value("solutions_counter"), "37");
+    assert!(fields.iter().all(|(key, value)| key == "classes" || value != "null"), "{:?}", fields);
+}
+
+#[test]
+fn counters_are_null_for_the_other_algorithms() {
+    for (algorithm, name) in [(Algorithm::TransferMatrix, "\"transfer\""), (Algorithm::Reference, "\"reference\"")] {
+        let fields = fields(&report(algorithm, Metrics::new()).to_json());
+        assert_eq!(fields.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(), KEYS);
+        for (key, value) in &fields {
+            let counter = KEYS[6..15].contains(&key.as_str());
+            assert_eq!(value == "null", counter || key == "classes", "{} in {:?}", key, fields);
+        }
+        assert_eq!(fields[4].1, name);
+    }
+}
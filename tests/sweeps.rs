@@ -0,0 +1,57 @@
+//! Sweeps over ranges of board sizes: which sizes are in the table, the rows of boards the counter can not
+//! handle, and the sizes skipped once a board has run over the time budget.
+
+use std::time::Duration;
+
+use roundtrip::{Algorithm, Count, Error, Outcome, Sweep, MAX_FRONTIER_WIDTH};
+
+fn sweep(n: std::ops::RangeInclusive<usize>, m: std::ops::RangeInclusive<usize>, algorithm: Algorithm, budget: Option<Duration>) -> Sweep {
+    Sweep {
+        n,
+        m,
+        algorithm,
+        budget,
+        folding: true,
+        connectivity: true,
+    }
+}
+
+#[test]
+fn invalid_sizes_are_left_out() {
+    // n > m, an odd number of vertices and boards under 12 vertices are not in the table
+    let sizes = sweep(2..=5, 2..=6, Algorithm::Search, None).sizes();
+    assert_eq!(sizes, [(2, 6), (3, 4), (3, 6), (4, 4), (4, 5), (4, 6), (5, 6)]);
+
+    let outcomes: Vec<Outcome> = sweep(4..=4, 3..=6, Algorithm::Search, None).run(|_| {}).into_iter().map(|row| row.outcome).collect();
+    let counts = [6u32, 14, 37].map(|count| Outcome::Counted(Count::from(count)));
+    assert_eq!(outcomes, counts);
+}
+
+#[test]
+fn boards_the_counter_can_not_handle_fail() {
+    let n = MAX_FRONTIER_WIDTH + 1;
+    let rows = sweep(n..=n, n..=n + 1, Algorithm::TransferMatrix, None).run(|_| {});
+    assert_eq!(rows.len(), 2);
+    for row in &rows {
+        // ... without keeping the larger board from being tried
+        assert_eq!(row.outcome, Outcome::Failed(Error::TooWide { n, max: MAX_FRONTIER_WIDTH }));
+        assert!(row.to_csv().starts_with(&format!("{},{},failed,,", n, row.m)), "{}", row.to_csv());
+        assert!(row.to_json().contains(r#""status":"failed","count":null"#), "{}", row.to_json());
+        assert!(row.to_json().contains(r#""error":"#), "{}", row.to_json());
+    }
+}
+
+#[test]
+fn sizes_after_one_over_the_budget_are_skipped() {
+    // the searches on 7 x 8 and 8 x 8 take seconds, so they are stopped, and the larger boards with the same n are not tried
+    let rows = sweep(7..=8, 8..=10, Algorithm::Search, Some(Duration::from_millis(10))).run(|_| {});
+    let outcomes: Vec<(usize, usize, &str)> = rows.iter().map(|row| (row.n, row.m, row.outcome.status())).collect();
+    let expected = [(7, 8, "timeout"), (7, 10, "skipped"), (8, 8, "timeout"), (8, 9, "skipped"), (8, 10, "skipped")];
+    assert_eq!(outcomes, expected);
+    assert!(rows[3].to_json().contains(r#""count":null"#));
+
+    // the transfer matrix can not be stopped, but a board that took longer than the budget is counted all the same
+    let rows = sweep(4..=4, 4..=6, Algorithm::TransferMatrix, Some(Duration::ZERO)).run(|_| {});
+    let outcomes: Vec<Outcome> = rows.into_iter().map(|row| row.outcome).collect();
+    assert_eq!(outcomes, [Outcome::Counted(Count::from(6u32)), Outcome::OverBudget, Outcome::OverBudget]);
+}
@@ -0,0 +1,78 @@
+//! Puzzles and solutions in the text format: what is read, where the errors are reported,
+//! and that writing and reading a board gives it back unchanged.
+
+use roundtrip::{parse_puzzle, write_puzzle, write_solution, Count, Error, Metrics, Puzzle};
+
+const EXAMPLE: &str = "\
+.-. . .
+|     x
+. . . .
+
+. . . .
+";
+
+fn parse_error(text: &str) -> (usize, usize) {
+    match parse_puzzle(text) {
+        Err(Error::Parse { line, column, .. }) => (line, column),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn parse_example() {
+    let puzzle = parse_puzzle(EXAMPLE).unwrap();
+    assert_eq!((puzzle.n(), puzzle.m()), (4, 3));
+    assert_eq!(puzzle.given_edges(), &[(0, 1), (0, 4)]);
+    assert_eq!(puzzle.forbidden_edges(), &[(3, 7)]);
+
+    // comments and blank lines around the board are skipped
+    let commented = format!("# a puzzle\n\n{}\n\n", EXAMPLE);
+    assert_eq!(parse_puzzle(&commented).unwrap(), puzzle);
+}
+
+#[test]
+fn parse_errors_point_at_the_mistake() {
+    assert_eq!(parse_error(""), (1, 1)); // no board
+    assert_eq!(parse_error(". . .-\n"), (1, 6)); // the row ends with an edge
+    assert_eq!(parse_error(". . . .\n|\n"), (3, 1)); // no row of dots after the edges
+    assert_eq!(parse_error(". . . .\n\n. o . .\n\n. . . .\n"), (3, 3)); // not a dot
+    assert_eq!(parse_error(". . . .\n-\n. . . .\n\n. . . .\n"), (2, 1)); // a horizontal edge between rows
+    assert_eq!(parse_error(". . . .\n\n.|. . .\n\n. . . .\n"), (3, 2)); // a vertical edge within a row
+    assert_eq!(parse_error(". . . .\n |\n. . . .\n\n. . . .\n"), (2, 2)); // an edge inside a tile
+    assert_eq!(parse_error(". . . .\n\n. . . . .\n\n. . . .\n"), (3, 8)); // a longer row
+    // line numbers count the comment lines as well
+    assert_eq!(parse_error("# a puzzle\n\n. . . .\n\n. . . o\n\n. . . .\n"), (5, 7));
+}
+
+#[test]
+fn write_and_parse_round_trip() {
+    assert_eq!(write_puzzle(&parse_puzzle(EXAMPLE).unwrap()), EXAMPLE);
+
+    let mut wide = Puzzle::new(6, 4).unwrap();
+    wide.add_given_edge(0, 1).unwrap();
+    wide.add_given_edge(6, 12).unwrap();
+    wide.add_forbidden_edge(11, 17).unwrap(); // (in the order they are read back, row by row)
+    wide.add_forbidden_edge(22, 23).unwrap();
+    let text = write_puzzle(&wide);
+    assert_eq!(text, ".-. . . . .\n\n. . . . . .\n|         x\n. . . . . .\n\n. . . . .x.\n");
+    assert_eq!(parse_puzzle(&text).unwrap(), wide);
+}
+
+#[test]
+fn solutions_written_as_boards() {
+    // a wide puzzle, solved on its transpose and written back the way it was drawn
+    let text = ".-.-. . . .\n|\n. . . . . .\n\n. . . . . .\n\n. . .x. . .\n";
+    let puzzle = parse_puzzle(text).unwrap();
+    let solutions = puzzle.solve(usize::MAX, &mut Metrics::new()).unwrap();
+    assert!(solutions.count > Count::from(0u32));
+    for cycle in &solutions.cycles {
+        let written = write_solution(&puzzle, cycle);
+        let board = parse_puzzle(&written).unwrap();
+        // every dot is on the loop, the given edges are drawn and the forbidden edge is kept
+        assert_eq!(board.given_edges().len(), 24, "{}", written);
+        assert!(puzzle.given_edges().iter().all(|&(a, b)| cycle.has_edge(a, b)), "{}", written);
+        assert!(board.given_edges().iter().all(|&(a, b)| cycle.has_edge(a, b)), "{}", written);
+        assert_eq!(board.forbidden_edges(), puzzle.forbidden_edges());
+        assert!(written.lines().last().unwrap().contains(".x."), "{}", written);
+    }
+}